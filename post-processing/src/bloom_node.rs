@@ -415,13 +415,16 @@ pub fn prepare_bloom_targets(
     let mut textures_map = HashMap::default();
 
     for (entity, camera) in &cameras {
-        if let Some(target_size) = camera.physical_target_size.clone() {
+        let target_size = camera
+            .physical_viewport_size
+            .or(camera.physical_target_size);
+        if let Some(target_size) = target_size {
             let mut textures_arr = vec![];
 
             for j in 0..2 {
                 textures_arr.push((0..MIPS).map(|i| {
                     textures_map
-                        .entry((camera.target.clone(), i, j))
+                        .entry((camera.target.clone(), target_size, i, j))
                         .or_insert_with(|| {
                             texture_cache.get(
                                 &render_device,
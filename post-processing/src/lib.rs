@@ -5,7 +5,7 @@ use bevy::ecs::prelude::*;
 use bevy::prelude::{Assets, HandleUntyped, Msaa, Shader};
 use bevy::reflect::TypeUuid;
 use bevy::render::{
-    render_graph::RenderGraph,
+    render_graph::{RenderGraph, SlotInfo, SlotType},
     RenderApp, RenderStage,
 };
 use bevy::render::camera::ExtractedCamera;
@@ -13,7 +13,7 @@ use bevy::render::render_resource::{Extent3d, Operations, ShaderStage, TextureDe
 use bevy::render::renderer::RenderDevice;
 use bevy::render::texture::TextureCache;
 use bevy::render::view::WindowSystem;
-use bevy::ui::draw_ui_graph;
+use bevy::ui::{draw_ui_graph, UiPassNode};
 use bevy::utils::HashMap;
 pub use color_material_custom::ColorMaterialCustom;
 pub use lights::*;
@@ -45,6 +45,10 @@ pub const CUSTOM_TONEMAPPING: HandleUntyped =
 pub const CUSTOM_MATERIAL: HandleUntyped =
     HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 8213474567257890329);
 
+/// Render graph that only draws the UI, for a camera over others that have viewports. The UI of
+/// cameras with viewports is squeezed into them.
+pub const UI_GRAPH: &str = "ui_only";
+
 impl Plugin for Core2dCustomPlugin {
     fn build(&self, app: &mut App) {
         let mut assets = app.world.resource_mut::<Assets<Shader>>();
@@ -110,8 +114,26 @@ impl Plugin for Core2dCustomPlugin {
         let pass_node_2d = MainPass2dNodeCustom::new(&mut render_app.world);
         let bloom_node = BloomNode::new(&mut render_app.world);
         let tone_mapping_node = ToneMappingNode::new(&mut render_app.world);
+        let ui_pass_node = UiPassNode::new(&mut render_app.world);
 
         let mut graph = render_app.world.resource_mut::<RenderGraph>();
+
+        let mut ui_graph = RenderGraph::default();
+        ui_graph.add_node(draw_ui_graph::node::UI_PASS, ui_pass_node);
+        let input_node_id = ui_graph.set_input(vec![SlotInfo::new(
+            draw_ui_graph::input::VIEW_ENTITY,
+            SlotType::Entity,
+        )]);
+        ui_graph
+            .add_slot_edge(
+                input_node_id,
+                draw_ui_graph::input::VIEW_ENTITY,
+                draw_ui_graph::node::UI_PASS,
+                UiPassNode::IN_VIEW,
+            )
+            .unwrap();
+        graph.add_sub_graph(UI_GRAPH, ui_graph);
+
         let draw_2d_graph = graph
             .get_sub_graph_mut(bevy::core_pipeline::core_2d::graph::NAME)
             .unwrap();
@@ -208,9 +230,14 @@ fn prepare_view_targets_custom(
     let mut textures = HashMap::default();
 
     for (entity, camera) in &cameras {
-        if let Some(target_size) = camera.physical_target_size {
+        // Cameras with a viewport get textures of just its size, the tone mapping pass then
+        // draws them into their part of the target
+        let target_size = camera
+            .physical_viewport_size
+            .or(camera.physical_target_size);
+        if let Some(target_size) = target_size {
             let texture_view = textures
-                .entry(camera.target.clone())
+                .entry((camera.target.clone(), target_size))
                 .or_insert_with(|| {
                     texture_cache.get(
                         &render_device,
@@ -235,7 +262,7 @@ fn prepare_view_targets_custom(
 
             let sampled_target = if msaa.samples > 1 {
                 let sampled_texture = sampled_textures
-                    .entry(camera.target.clone())
+                    .entry((camera.target.clone(), target_size))
                     .or_insert_with(|| {
                         texture_cache.get(
                             &render_device,
@@ -63,7 +63,7 @@ impl Node for MainPass2dNodeCustom {
     ) -> Result<(), NodeRunError> {
         let view_entity = graph.get_input_entity(Self::IN_VIEW)?;
 
-        if let Ok((camera, transparent_phase, target_custom, camera_2d)) =
+        if let Ok((_camera, transparent_phase, target_custom, camera_2d)) =
             self.query.get_manual(world, view_entity)
         {
             let pass_descriptor = RenderPassDescriptor {
@@ -88,10 +88,8 @@ impl Node for MainPass2dNodeCustom {
                 .begin_render_pass(&pass_descriptor);
 
             let mut draw_functions = draw_functions.write();
+            // The custom target is already the size of the viewport
             let mut tracked_pass = TrackedRenderPass::new(render_pass);
-            if let Some(viewport) = camera.viewport.as_ref() {
-                tracked_pass.set_camera_viewport(viewport);
-            }
             for item in &transparent_phase.items {
                 let draw_function = draw_functions.get_mut(item.draw_function).unwrap();
                 draw_function.draw(world, &mut tracked_pass, view_entity, item);
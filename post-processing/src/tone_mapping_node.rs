@@ -14,7 +14,7 @@ use bevy::render::renderer::{RenderContext, RenderDevice, RenderQueue};
 use bevy::render::texture::BevyDefault;
 use bevy::render::view::{ExtractedView, ViewTarget};
 use wgpu::{
-    BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor, BindingResource,
+    BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor, BindingResource, LoadOp,
     Operations, RenderPassColorAttachment, RenderPassDescriptor,
 };
 
@@ -85,7 +85,14 @@ impl Node for ToneMappingNode {
             color_attachments: &[Some(RenderPassColorAttachment {
                 view: &target.view,
                 resolve_target: None,
-                ops: Operations::default(),
+                // Split views share the window, clearing it would wipe the other one
+                ops: match camera.viewport {
+                    Some(_) => Operations {
+                        load: LoadOp::Load,
+                        store: true,
+                    },
+                    None => Operations::default(),
+                },
             })],
             depth_stencil_attachment: None,
         };
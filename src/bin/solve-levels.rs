@@ -0,0 +1,52 @@
+//! Checks that every level in the registry can still be finished.
+//!
+//! `cargo run --bin solve-levels [level id...]` prints the shortest merge sequence for each
+//! level and exits with an error if any of them is unsolvable.
+
+use std::path::Path;
+use std::process::ExitCode;
+
+use combobox_game::levels::{read_levels, read_recipes};
+use combobox_game::solver::{solve, Solution};
+
+fn main() -> ExitCode {
+    let ids: Vec<String> = std::env::args().skip(1).collect();
+    let assets = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+
+    let (levels, recipes) = match read_levels(&assets).and_then(|levels| {
+        let recipes = read_recipes(&assets)?;
+        Ok((levels, recipes))
+    }) {
+        Ok(read) => read,
+        Err(error) => {
+            eprintln!("{}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut failed = false;
+
+    for (info, description) in levels {
+        if !ids.is_empty() && !ids.contains(&info.id) {
+            continue;
+        }
+
+        match description {
+            Ok(description) => {
+                let solution = solve(&description, &recipes);
+                println!("{}: {}", info.id, solution);
+                failed |= matches!(solution, Solution::Unsolvable);
+            }
+            Err(error) => {
+                println!("{}: {}", info.id, error);
+                failed = true;
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
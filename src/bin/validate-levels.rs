@@ -0,0 +1,46 @@
+//! Loads every level in the registry without rendering and reports structural problems.
+//!
+//! `cargo run --bin validate-levels` exits with an error if any level has problems, so it can
+//! be used to gate merges.
+
+use std::path::Path;
+use std::process::ExitCode;
+
+use combobox_game::levels::{read_levels, validate};
+
+fn main() -> ExitCode {
+    let assets = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+
+    let levels = match read_levels(&assets) {
+        Ok(levels) => levels,
+        Err(error) => {
+            eprintln!("{}", error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut failed = false;
+
+    for (info, description) in levels {
+        let errors = match description {
+            Ok(description) => validate(&description, &assets),
+            Err(error) => vec![error],
+        };
+
+        if errors.is_empty() {
+            println!("{}: ok", info.id);
+        } else {
+            failed = true;
+            println!("{}:", info.id);
+            for error in errors {
+                println!("  {}", error);
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
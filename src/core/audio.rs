@@ -2,7 +2,8 @@ use std::{path::PathBuf, time::Duration};
 
 use bevy::{
     prelude::{
-        App, AssetServer, Assets, Commands, Entity, Handle, Local, Plugin, Query, Res, ResMut, SystemSet,
+        App, AssetServer, Assets, Commands, Entity, EventReader, Handle, Local, Plugin, Query, Res,
+        ResMut, SystemSet,
     },
     utils::{HashMap, Instant},
 };
@@ -10,7 +11,7 @@ use bevy_kira_audio::{Audio, AudioControl, AudioEasing, AudioInstance, AudioTwee
 
 use crate::states::AudioState;
 
-use super::Player;
+use super::{ComboboxMerged, ComboboxUndone, LevelFinished, Player};
 
 pub struct BackgroundMusicHandle(Handle<AudioInstance>);
 
@@ -30,6 +31,8 @@ impl Plugin for AudioPlugin {
         app.add_system_set(SystemSet::on_enter(AudioState::Menu).with_system(setup_menu_music));
         app.add_system(play_background_music);
         app.add_system(play_player_movement_sound);
+        app.add_system(play_box_join_sound);
+        app.add_system(play_finish_sound);
 
         app.insert_resource(BackgroundMusic(Some(
             "audio/main_menu_background.ogg".to_string(),
@@ -103,4 +106,28 @@ fn play_player_movement_sound(
     }
 }
 
-fn play_box_join_sound() {}
+fn play_box_join_sound(
+    mut merged: EventReader<ComboboxMerged>,
+    mut undone: EventReader<ComboboxUndone>,
+    audio: Res<Audio>,
+    assets: Res<AssetServer>,
+) {
+    let outputs = merged
+        .iter()
+        .map(|event| event.outputs.len())
+        .chain(undone.iter().map(|event| event.outputs.len()));
+
+    for _ in 0..outputs.sum() {
+        audio.play(assets.load("audio/box_join.ogg"));
+    }
+}
+
+fn play_finish_sound(
+    mut events: EventReader<LevelFinished>,
+    audio: Res<Audio>,
+    assets: Res<AssetServer>,
+) {
+    for _ in events.iter() {
+        audio.play(assets.load("audio/finish.ogg")).with_volume(0.8);
+    }
+}
@@ -1,63 +1,405 @@
 use bevy::prelude::*;
+use bevy::render::camera::Viewport;
 use bevy::sprite::Rect;
+use bevy_rapier2d::prelude::*;
 
-use crate::core::{Player, SceneBoundaries};
-use crate::states::CameraState;
+use crate::core::{
+    BodyGravity, GravitySystem, Player, PlayerIndex, SceneBoundaries, SimulationApp, SimulationStep,
+};
+use crate::states::{CameraState, LevelState};
+use crate::utils::SceneDirection;
+
+/// Turns rotating the view with gravity on or off, in levels that don't decide it themselves.
+pub const ROTATE_CAMERA_KEY: KeyCode = KeyCode::F3;
+
+/// How quickly the camera turns, like [`FOLLOW_SPEED`].
+const ROTATION_SPEED: f32 = 5.0;
+
+/// Chosen by the player, levels can override it with
+/// [`crate::levels::LevelDescription::rotate_camera`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CameraSettings {
+    /// Turns the view so that gravity always points down the screen.
+    pub rotate_with_gravity: bool,
+}
+
+/// Which way down the screen points in the level, the way the camera turns to when it rotates
+/// with gravity. Players move by the keys for the directions they see, so it is worked out
+/// every simulation step, see [`ScreenDownSystem`].
+#[derive(Debug, Clone, Copy)]
+pub struct ScreenDown {
+    pub direction: SceneDirection,
+    /// Whether `direction` turns with gravity, from the level or the [`CameraSettings`]. A replay
+    /// sets it to what it was in each step when it was recorded.
+    pub rotate: bool,
+}
+
+impl Default for ScreenDown {
+    fn default() -> Self {
+        ScreenDown {
+            direction: SceneDirection::Down,
+            rotate: false,
+        }
+    }
+}
+
+impl ScreenDown {
+    /// `direction` in the level, as it points on the screen.
+    pub fn on_screen(&self, direction: SceneDirection) -> SceneDirection {
+        SceneDirection::from_index(direction.get_index() + 4 - self.direction.get_index())
+    }
+}
+
+/// Runs after [`ScreenDown`] points where the players see down in this step.
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ScreenDownSystem;
 
 pub struct CameraPlugin;
 
 /// Controls camera zoom and movement during the game.
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<CameraSettings>();
+        app.init_resource::<ScreenDown>();
+        app.add_system(toggle_rotation);
+        // Also without a camera following the players, their keys depend on it
+        app.add_system_set(
+            SystemSet::on_update(LevelState::Level)
+                .with_system(update_rotation.after(toggle_rotation)),
+        );
+        app.add_simulation_system_set_to_stage(
+            SimulationStep::Update,
+            SystemSet::on_update(LevelState::Level).with_system(
+                update_screen_down
+                    .after(GravitySystem)
+                    .label(ScreenDownSystem),
+            ),
+        );
         app.add_system_set(
-            SystemSet::on_update(CameraState::FollowPlayers).with_system(follow_players),
+            SystemSet::on_update(CameraState::FollowPlayers)
+                .with_system(follow_players)
+                .with_system(rotate_with_gravity),
         );
+        // Levels are edited the right way up
+        app.add_system_set(SystemSet::on_enter(LevelState::Editor).with_system(straighten));
+        app.add_system_set(SystemSet::on_exit(CameraState::FollowPlayers).with_system(merge_views));
     }
 }
 
+fn toggle_rotation(keys: Res<Input<KeyCode>>, mut settings: ResMut<CameraSettings>) {
+    if keys.just_pressed(ROTATE_CAMERA_KEY) {
+        settings.rotate_with_gravity = !settings.rotate_with_gravity;
+    }
+}
+
+fn update_rotation(
+    settings: Res<CameraSettings>,
+    boundaries: Res<SceneBoundaries>,
+    mut screen_down: ResMut<ScreenDown>,
+) {
+    let rotate = boundaries
+        .rotate_with_gravity
+        .unwrap_or(settings.rotate_with_gravity);
+
+    if screen_down.rotate != rotate {
+        screen_down.rotate = rotate;
+    }
+}
+
+/// Players sharing a gravity see it pointing down, with gravities of their own they see the
+/// level's.
+fn update_screen_down(
+    players: Query<&BodyGravity, With<Player>>,
+    config: Res<RapierConfiguration>,
+    mut screen_down: ResMut<ScreenDown>,
+) {
+    let mut directions = players.iter().map(BodyGravity::direction);
+    let shared = directions
+        .next()
+        .filter(|first| directions.all(|direction| direction == *first));
+    let down = if screen_down.rotate {
+        shared.unwrap_or_else(|| SceneDirection::from_gravity_direction(config.gravity))
+    } else {
+        SceneDirection::Down
+    };
+
+    if screen_down.direction != down {
+        screen_down.direction = down;
+    }
+}
+
+fn rotate_with_gravity(
+    mut cameras: Query<&mut Transform, (With<GameCamera>, Without<Player>)>,
+    screen_down: Res<ScreenDown>,
+    time: Res<Time>,
+) {
+    let target = Quat::from_rotation_arc_2d(Vec2::NEG_Y, screen_down.direction.get_vec());
+
+    let smoothing = 1.0 - (-ROTATION_SPEED * time.delta_seconds()).exp();
+    for mut transform in cameras.iter_mut() {
+        transform.rotation = transform.rotation.slerp(target, smoothing);
+    }
+}
+
+fn straighten(mut cameras: Query<&mut Transform, With<GameCamera>>) {
+    for mut transform in cameras.iter_mut() {
+        transform.rotation = Quat::IDENTITY;
+    }
+}
+
+/// Players and foci further in than this from the edges of the view, in pixels.
+const FRAMING_MARGIN: f32 = 200.0;
+
+/// How quickly the camera catches up with what it frames, the remaining distance shrinks by
+/// `1 - exp(-FOLLOW_SPEED * seconds)`. The same as 5% a frame at 60 frames per second.
+const FOLLOW_SPEED: f32 = 3.0;
+
+/// How far out from the view range the camera may zoom to keep two players in one view, before
+/// the screen is split between them.
+const MAX_ZOOM_OUT: f32 = 2.0;
+
+/// Split views merge once the players fit this much closer in than the farthest zoom, so they
+/// don't flicker between the two at the edge.
+const MERGE_RATIO: f32 = 0.8;
+
+/// Kept in view together with the players.
+#[derive(Component, Debug, Clone)]
+pub struct CameraFocus;
+
+/// Shows the level. The first one covers the whole window, the second one is only active while
+/// the screen is split and follows the second player.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameCamera {
+    pub index: usize,
+}
+
+type TargetQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static GlobalTransform, Option<&'static Player>),
+    Or<(With<Player>, With<CameraFocus>)>,
+>;
+
+type CameraQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Transform,
+        &'static mut Camera,
+        &'static GameCamera,
+    ),
+    Without<Player>,
+>;
+
+/// Frames every player and [`CameraFocus`]. The camera zooms out for them as far as the level
+/// boundaries allow, and never in closer than the view range of the level. When two players get
+/// too far apart for that the screen is split, with a view following each of them.
 fn follow_players(
-    mut players: Query<&GlobalTransform, With<Player>>,
-    mut cameras: Query<(&mut Transform, &Camera), (With<Camera2d>, Without<Player>)>,
+    targets: TargetQuery,
+    mut cameras: CameraQuery,
+    windows: Res<Windows>,
     boundaries: Res<SceneBoundaries>,
+    time: Res<Time>,
 ) {
-    for player in players.iter_mut() {
-        for (mut transform, camera) in cameras.iter_mut() {
-            if let Some(view_range) = boundaries.view_range {
-                let view_rect = get_view_rect(&camera, &transform);
-                let view_size = (view_rect.max - view_rect.min) * 0.5;
-                let zoom = if view_size.x > view_size.y {
-                    view_range / view_size.y
-                } else {
-                    view_range / view_size.x
-                };
-
-                transform.scale *= zoom.powf(0.05);
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => return,
+    };
+    let window_size = Vec2::new(window.width(), window.height());
+    let physical_size = UVec2::new(window.physical_width(), window.physical_height());
+
+    let first_transform = match cameras
+        .iter()
+        .find(|(.., game_camera)| game_camera.index == 0)
+    {
+        Some((transform, ..)) => *transform,
+        None => return,
+    };
+    let rotation = first_transform.rotation;
+    let was_split = cameras
+        .iter()
+        .any(|(_, camera, game_camera)| game_camera.index == 1 && camera.is_active);
+
+    // Framed as the camera sees it, which may be rotated
+    let to_view = rotation.inverse();
+    let all = targets
+        .iter()
+        .map(|(target, _)| (to_view * target.translation()).truncate())
+        .collect::<Vec<_>>();
+    let mut players = targets
+        .iter()
+        .filter_map(|(target, player)| match player?.index {
+            PlayerIndex::TwoPlayers(index) => {
+                Some((index, (to_view * target.translation()).truncate()))
+            }
+            PlayerIndex::SinglePlayer => None,
+        })
+        .collect::<Vec<_>>();
+    players.sort_by_key(|(index, _)| *index);
+
+    // Whether one view of the whole window could still frame both players
+    let split = match players[..] {
+        [(_, first), (_, second)] => {
+            let unit_view = window_size * 0.5;
+            let farthest = closest_zoom(&boundaries, unit_view) * MAX_ZOOM_OUT;
+            let limit = zoom_limit(&boundaries, rotation, unit_view).min(farthest);
+            let needed = framing(&[first, second], unit_view);
+            if was_split {
+                needed >= limit * MERGE_RATIO
+            } else {
+                needed > limit
             }
+            .then(|| split_viewports(first, second, physical_size))
+        }
+        _ => None,
+    };
 
-            let mut pos: Vec2 =
-                player.translation().truncate() * 0.05 + transform.translation.truncate() * 0.95;
+    let smoothing = 1.0 - (-FOLLOW_SPEED * time.delta_seconds()).exp();
 
-            // Clamp camera to boundaries
-            if let Some(boundaries) = boundaries.rect {
-                let view_rect = get_view_rect(&camera, &transform);
-                let view_size = (view_rect.max - view_rect.min) * 0.5;
-                pos = clamp_to_rect(pos, view_size, boundaries);
+    for (mut transform, mut camera, game_camera) in cameras.iter_mut() {
+        let (viewport, framed) = match &split {
+            Some(viewports) => {
+                let framed = players
+                    .iter()
+                    .filter(|(index, _)| *index == game_camera.index)
+                    .map(|(_, position)| *position)
+                    .collect::<Vec<_>>();
+                (Some(viewports[game_camera.index].clone()), framed)
             }
+            None if game_camera.index == 0 => (None, all.clone()),
+            None => (None, vec![]),
+        };
 
-            transform.translation = pos.extend(transform.translation.z);
+        let is_active = game_camera.index == 0 || split.is_some();
+        if camera.is_active != is_active {
+            camera.is_active = is_active;
+            // The second view starts from the shared one
+            if is_active {
+                *transform = first_transform;
+            }
+        }
+        // Viewports can't be compared, and changing the camera updates its projection
+        let area = |viewport: &Option<Viewport>| {
+            viewport
+                .as_ref()
+                .map(|viewport| (viewport.physical_position, viewport.physical_size))
+        };
+        if area(&camera.viewport) != area(&viewport) {
+            camera.viewport = viewport;
         }
+        if framed.is_empty() {
+            continue;
+        }
+
+        // What the camera sees at a scale of one, its projection may not have caught up with a
+        // new viewport yet
+        let unit_view = match &camera.viewport {
+            Some(viewport) => viewport.physical_size.as_vec2() / window.scale_factor() as f32 * 0.5,
+            None => window_size * 0.5,
+        };
+
+        let center = {
+            let (min, max) = bounds(&framed);
+            (transform.rotation * ((min + max) * 0.5).extend(0.0)).truncate()
+        };
+        let target_scale = framing(&framed, unit_view)
+            .min(zoom_limit(&boundaries, transform.rotation, unit_view))
+            .max(closest_zoom(&boundaries, unit_view));
+        // Zooming follows the ratio, so it is as smooth zoomed in as out
+        let scale = transform.scale.x;
+        transform.scale *= (target_scale / scale).powf(smoothing);
+
+        let mut pos = transform.translation.truncate().lerp(center, smoothing);
+
+        // Clamp camera to boundaries
+        if let Some(rect) = boundaries.rect {
+            let view_size = rotated_extent(transform.rotation, unit_view * transform.scale.x);
+            pos = clamp_to_rect(pos, view_size, rect);
+        }
+
+        transform.translation = pos.extend(transform.translation.z);
     }
 }
 
-fn get_view_rect(camera: &Camera, camera_transform: &Transform) -> Rect {
-    let matrix = camera_transform.compute_matrix() * camera.projection_matrix().inverse();
+/// Back to a single view, for menus and the editor.
+fn merge_views(mut cameras: Query<(&mut Camera, &GameCamera)>) {
+    for (mut camera, game_camera) in cameras.iter_mut() {
+        camera.viewport = None;
+        camera.is_active = game_camera.index == 0;
+    }
+}
 
-    let max = matrix.project_point3(Vec2::ONE.extend(-1.0)).truncate();
-    let min = matrix.project_point3(Vec2::NEG_ONE.extend(-1.0)).truncate();
+fn bounds(points: &[Vec2]) -> (Vec2, Vec2) {
+    points.iter().fold(
+        (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+        |(min, max), point| (min.min(*point), max.max(*point)),
+    )
+}
 
-    Rect {
-        min: min.min(max),
-        max: max.max(min),
+/// Scale at which a view of `unit_view` half size frames all of `points`.
+fn framing(points: &[Vec2], unit_view: Vec2) -> f32 {
+    let (min, max) = bounds(points);
+    let half_size = (max - min) * 0.5 + FRAMING_MARGIN;
+    (half_size / unit_view).max_element()
+}
+
+/// Scale at which the view fills the level boundaries.
+fn zoom_limit(boundaries: &SceneBoundaries, rotation: Quat, unit_view: Vec2) -> f32 {
+    match boundaries.rect {
+        Some(rect) => {
+            ((rect.max - rect.min) * 0.5 / rotated_extent(rotation, unit_view)).min_element()
+        }
+        None => f32::INFINITY,
+    }
+}
+
+/// The camera never zooms in closer than the view range of the level.
+fn closest_zoom(boundaries: &SceneBoundaries, unit_view: Vec2) -> f32 {
+    boundaries
+        .view_range
+        .map_or(1.0, |view_range| view_range / unit_view.min_element())
+}
+
+/// Half size of the area of the world a rotated view of `half_size` covers.
+fn rotated_extent(rotation: Quat, half_size: Vec2) -> Vec2 {
+    let matrix = Mat3::from_quat(rotation);
+    Vec2::new(
+        matrix.x_axis.x.abs() * half_size.x + matrix.y_axis.x.abs() * half_size.y,
+        matrix.x_axis.y.abs() * half_size.x + matrix.y_axis.y.abs() * half_size.y,
+    )
+}
+
+/// Halves of the window for each player, split across the line between them so each one gets
+/// the half on their side.
+fn split_viewports(first: Vec2, second: Vec2, physical_size: UVec2) -> [Viewport; 2] {
+    let offset = second - first;
+    let size = physical_size.as_vec2();
+    let (half_size, position) = if offset.x.abs() * size.y >= offset.y.abs() * size.x {
+        (
+            UVec2::new(physical_size.x / 2, physical_size.y),
+            UVec2::new(physical_size.x / 2, 0),
+        )
+    } else {
+        (
+            UVec2::new(physical_size.x, physical_size.y / 2),
+            UVec2::new(0, physical_size.y / 2),
+        )
+    };
+    // Viewports are y-down, the view is y-up
+    let second_after = if position.x > 0 {
+        offset.x > 0.0
+    } else {
+        offset.y < 0.0
+    };
+    let viewport = |physical_position| Viewport {
+        physical_position,
+        physical_size: half_size,
+        ..default()
+    };
+    if second_after {
+        [viewport(UVec2::ZERO), viewport(position)]
+    } else {
+        [viewport(position), viewport(UVec2::ZERO)]
     }
 }
 
@@ -75,3 +417,48 @@ fn clamp_to_rect(pos: Vec2, view_half_size: Vec2, rect: Rect) -> Vec2 {
         })
         .into()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn areas(viewports: [Viewport; 2]) -> [(UVec2, UVec2); 2] {
+        viewports.map(|viewport| (viewport.physical_position, viewport.physical_size))
+    }
+
+    #[test]
+    fn players_side_by_side_split_left_and_right() {
+        let size = UVec2::new(1600, 900);
+        let left = (UVec2::ZERO, UVec2::new(800, 900));
+        let right = (UVec2::new(800, 0), UVec2::new(800, 900));
+
+        let viewports = split_viewports(Vec2::ZERO, Vec2::new(1000.0, 300.0), size);
+        assert_eq!(areas(viewports), [left, right]);
+        let viewports = split_viewports(Vec2::ZERO, Vec2::new(-1000.0, 300.0), size);
+        assert_eq!(areas(viewports), [right, left]);
+    }
+
+    #[test]
+    fn players_above_each_other_split_top_and_bottom() {
+        let size = UVec2::new(1600, 900);
+        let top = (UVec2::ZERO, UVec2::new(1600, 450));
+        let bottom = (UVec2::new(0, 450), UVec2::new(1600, 450));
+
+        let viewports = split_viewports(Vec2::ZERO, Vec2::new(300.0, 1000.0), size);
+        assert_eq!(areas(viewports), [bottom, top]);
+        let viewports = split_viewports(Vec2::ZERO, Vec2::new(300.0, -1000.0), size);
+        assert_eq!(areas(viewports), [top, bottom]);
+    }
+
+    #[test]
+    fn framing_fits_points_and_margin() {
+        let unit_view = Vec2::new(400.0, 300.0);
+
+        let points = [Vec2::new(-100.0, 50.0), Vec2::new(300.0, 250.0)];
+        assert_eq!(framing(&points, unit_view), 1.0);
+        // The narrower side decides
+        assert_eq!(framing(&[Vec2::ZERO], unit_view), FRAMING_MARGIN / 300.0);
+        let tall = [Vec2::ZERO, Vec2::new(0.0, 600.0)];
+        assert_eq!(framing(&tall, unit_view), (300.0 + FRAMING_MARGIN) / 300.0);
+    }
+}
@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+
+use crate::core::BoxKind;
+
+/// Things that happen in a level, for audio, stats and effects to react to without the
+/// gameplay systems knowing about them.
+pub struct EventsPlugin;
+
+impl Plugin for EventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ComboboxMerged>();
+        app.add_event::<ComboboxUndone>();
+        app.add_event::<GravityChanged>();
+        app.add_event::<DoorOpened>();
+        app.add_event::<DoorClosed>();
+        app.add_event::<ButtonPressed>();
+        app.add_event::<ButtonReleased>();
+        app.add_event::<PlayerGrabbed>();
+        app.add_event::<PlayerJumped>();
+        app.add_event::<LevelFinished>();
+    }
+}
+
+/// Two boxes touched and were replaced. The inputs are despawned over the next frames.
+#[derive(Debug, Clone)]
+pub struct ComboboxMerged {
+    pub inputs: [Entity; 2],
+    pub outputs: Vec<Entity>,
+    /// Kind of the new box.
+    pub kind: BoxKind,
+}
+
+/// An undo box split `combobox` back into the boxes it was merged from.
+#[derive(Debug, Clone)]
+pub struct ComboboxUndone {
+    pub undo: Entity,
+    pub combobox: Entity,
+    /// The other pieces, when `combobox` is a piece of a split box.
+    pub pieces: Vec<Entity>,
+    pub outputs: Vec<Entity>,
+}
+
+/// The gravity of the whole level, in pixels per second squared.
+#[derive(Debug, Clone)]
+pub struct GravityChanged {
+    pub from: Vec2,
+    pub to: Vec2,
+}
+
+/// The door is fully open.
+#[derive(Debug, Clone)]
+pub struct DoorOpened {
+    pub door: Entity,
+}
+
+/// The door is fully closed.
+#[derive(Debug, Clone)]
+pub struct DoorClosed {
+    pub door: Entity,
+}
+
+#[derive(Debug, Clone)]
+pub struct ButtonPressed {
+    pub button: Entity,
+    pub signal: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ButtonReleased {
+    pub button: Entity,
+    pub signal: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlayerGrabbed {
+    pub player: Entity,
+    pub combobox: Entity,
+}
+
+/// The player took off from the ground.
+#[derive(Debug, Clone)]
+pub struct PlayerJumped {
+    pub player: Entity,
+}
+
+/// All players stayed at a finish point long enough.
+#[derive(Debug, Clone)]
+pub struct LevelFinished {
+    pub level: String,
+}
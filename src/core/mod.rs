@@ -3,40 +3,35 @@ use bevy_rapier2d::prelude::*;
 
 mod audio;
 mod camera;
+mod events;
 mod material;
 mod scene_builder;
 mod scene_objects;
+mod timestep;
 
 pub use audio::*;
 pub use camera::*;
+pub use events::*;
 pub use material::*;
 pub use scene_builder::*;
 pub use scene_objects::*;
-
-use self::audio::AudioPlugin;
+pub use timestep::*;
 
 pub struct CorePlugin;
 
 impl Plugin for CorePlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugin(RapierPhysicsPlugin::<NoUserData>::default());
-
-        #[cfg(debug_assertions)]
-        app.add_plugin(RapierDebugRenderPlugin::default());
+        // Physics runs in the simulation steps, see `TimestepPlugin`
+        app.add_plugin(
+            RapierPhysicsPlugin::<NoUserData>::default().with_default_system_setup(false),
+        );
 
         app.init_resource::<SceneBoundaries>();
 
+        app.add_plugin(EventsPlugin);
         app.add_plugin(CameraPlugin);
         app.add_plugin(AudioPlugin);
         app.add_plugin(SceneObjectsPlugin);
-
-        app.insert_resource(RapierConfiguration {
-            timestep_mode: TimestepMode::Variable {
-                max_dt: 1.0 / 30.0,
-                time_scale: 1.0,
-                substeps: 1,
-            },
-            ..default()
-        });
+        app.add_plugin(TimestepPlugin);
     }
 }
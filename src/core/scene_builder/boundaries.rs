@@ -6,6 +6,10 @@ use bevy::sprite::Rect;
 pub struct SceneBoundaries {
     pub rect: Option<Rect>,
     pub view_range: Option<f32>,
+    /// Overrides [`crate::core::CameraSettings::rotate_with_gravity`].
+    pub rotate_with_gravity: Option<bool>,
+    /// Finish points spawned from now on are kept in view, see [`crate::core::CameraFocus`].
+    pub frame_finish: bool,
 }
 
 impl<'w, 's, 'a, 'b> SceneBuilder<'w, 's, 'a, 'b> {
@@ -21,4 +25,12 @@ impl<'w, 's, 'a, 'b> SceneBuilder<'w, 's, 'a, 'b> {
     pub fn set_min_view_range(&mut self, range: f32) {
         self.boundaries.view_range = Some(range * Self::CELL_SIZE);
     }
+
+    pub fn set_frame_finish(&mut self, frame_finish: bool) {
+        self.boundaries.frame_finish = frame_finish;
+    }
+
+    pub fn set_camera_rotation(&mut self, rotate_with_gravity: bool) {
+        self.boundaries.rotate_with_gravity = Some(rotate_with_gravity);
+    }
 }
@@ -6,8 +6,8 @@ use bevy_rapier2d::prelude::*;
 use post_processing::PointLight2d;
 
 use crate::core::{
-    material_from_texture_and_emissive, Combobox, ComboboxState, ComboboxType, Material,
-    SceneBuilder,
+    material_from_texture_and_emissive, BodyGravity, Combobox, ComboboxState, ComboboxType,
+    Material, SceneBuilder,
 };
 
 #[derive(Bundle)]
@@ -25,6 +25,7 @@ pub struct ComboboxBundle {
     pub velocity: Velocity,
     pub point_light: PointLight2d,
     pub friction: Friction,
+    pub gravity: BodyGravity,
 }
 
 impl ComboboxBundle {
@@ -47,38 +48,28 @@ impl ComboboxBundle {
             }
             ComboboxType::Buff(_) => Color::rgb_u8(50, 91, 227),
             ComboboxType::Undo => Color::rgb_u8(141, 50, 227),
+            ComboboxType::Split(_) => Color::rgb_u8(227, 50, 141),
             ComboboxType::Gravity => Color::rgb_u8(232, 67, 56),
             ComboboxType::Direction { .. } => Color::rgb_u8(29, 196, 91),
             ComboboxType::Lamp { color } => color * 2.5,
+            ComboboxType::Boots => Color::rgb_u8(240, 200, 60),
         };
 
         let overlay = match combobox.box_type {
-            ComboboxType::Undo => Some(assets.load("images/overlay-undo.png")),
-            ComboboxType::Direction { direction } => {
-                if direction.y > 0.5 {
-                    Some(assets.load("images/overlay-up.png"))
-                } else if direction.y < -0.5 {
-                    Some(assets.load("images/overlay-down.png"))
-                } else if direction.x < -0.5 {
-                    Some(assets.load("images/overlay-left.png"))
-                } else {
-                    Some(assets.load("images/overlay-right.png"))
-                }
-            }
-            ComboboxType::Buff(buff) => {
-                if (buff - 3.0).abs() < 0.1 {
-                    Some(assets.load("images/overlay-x3.png"))
-                } else if (buff - 9.0).abs() < 0.1 {
-                    Some(assets.load("images/overlay-x9.png"))
-                } else if (buff - 2.0).abs() < 0.1 {
-                    Some(assets.load("images/overlay-x2.png"))
-                } else {
-                    Some(assets.load("images/overlay-x4.png"))
-                }
-            }
-            ComboboxType::Gravity => Some(assets.load("images/overlay-gravity.png")),
-            _ => None,
-        };
+            // Not every buff value has an image, `validate-levels` reports those
+            ComboboxType::Buff(_) => Some(
+                combobox
+                    .box_type
+                    .overlay()
+                    .unwrap_or("images/overlay-x4.png"),
+            ),
+            // Shows which way the player wearing them will fall
+            ComboboxType::Boots => combobox
+                .local_gravity
+                .and_then(|direction| ComboboxType::Direction { direction }.overlay()),
+            _ => combobox.box_type.overlay(),
+        }
+        .map(|path| assets.load(path));
 
         let image = assets.load("images/box-default-2.png");
 
@@ -115,6 +106,7 @@ impl ComboboxBundle {
                 coefficient: 0.9,
                 ..default()
             },
+            gravity: BodyGravity::default(),
         }
     }
 }
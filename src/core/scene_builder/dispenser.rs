@@ -0,0 +1,28 @@
+use bevy::prelude::shape::Quad;
+use bevy::prelude::*;
+use bevy::sprite::{MaterialMesh2dBundle, Mesh2dHandle};
+
+use crate::core::{BoxDispenser, Combobox, SceneBuilder};
+
+impl<'w, 's, 'a, 'b> SceneBuilder<'w, 's, 'a, 'b> {
+    pub fn spawn_dispenser_xy(&mut self, combobox: Combobox, x: f32, y: f32, signal: &str) {
+        self.spawn_dispenser(combobox, Vec2::new(x, y), signal);
+    }
+
+    pub fn spawn_dispenser(&mut self, combobox: Combobox, position: Vec2, signal: &str) {
+        let position = position * Self::CELL_SIZE;
+        let size = Vec2::splat(combobox.world_size() + 10.0);
+
+        self.builder
+            .spawn_bundle(MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(self.meshes.add(Quad::new(size).into())),
+                material: self.materials.add(Color::rgb_u8(33, 41, 36).into()),
+                transform: Transform::from_xyz(position.x, position.y, Self::DOOR_DEPTH),
+                ..default()
+            })
+            .insert(BoxDispenser {
+                combobox,
+                signal: signal.to_string(),
+            });
+    }
+}
@@ -3,13 +3,19 @@ use bevy::prelude::*;
 use bevy::sprite::{MaterialMesh2dBundle, Mesh2dHandle};
 use bevy_rapier2d::prelude::*;
 
-use crate::core::{collision_groups, Door, DoorButton, Material, SceneBuilder};
+use bevy::ecs::system::EntityCommands;
+
+use crate::core::{
+    collision_groups, ButtonMode, Door, DoorButton, Material, PressurePlate, PressurePlateGauge,
+    SceneBuilder,
+};
 use crate::utils::SceneDirection;
 
 #[derive(Bundle)]
 pub struct DoorBundle {
     pub door: Door,
     pub rigid_body: RigidBody,
+    pub velocity: Velocity,
     pub collider: Collider,
     #[bundle]
     pub mesh_bundle: MaterialMesh2dBundle<Material>,
@@ -24,7 +30,8 @@ impl DoorBundle {
 
         DoorBundle {
             door,
-            rigid_body: RigidBody::KinematicPositionBased,
+            rigid_body: RigidBody::KinematicVelocityBased,
+            velocity: Velocity::zero(),
             collider: Collider::cuboid(size.x * 0.5, size.y * 0.5),
             mesh_bundle: MaterialMesh2dBundle {
                 mesh: Mesh2dHandle(meshes.add(Quad::new(size).into())),
@@ -43,23 +50,29 @@ impl<'w, 's, 'a, 'b> SceneBuilder<'w, 's, 'a, 'b> {
         y: f32,
         height: f32,
         direction: SceneDirection,
-        pressed_mask: u32,
-        not_pressed_mask: u32,
+        signal: &str,
     ) {
-        self.spawn_door(
-            Vec2::new(x, y),
-            height,
-            direction,
-            pressed_mask,
-            not_pressed_mask,
-        );
+        self.spawn_door(Vec2::new(x, y), height, direction, signal);
     }
 
-    pub fn spawn_button_xy(&mut self, x: f32, y: f32, direction: SceneDirection, mask: u32) {
-        self.spawn_button(Vec2::new(x, y), direction, mask);
+    pub fn spawn_button_xy(
+        &mut self,
+        x: f32,
+        y: f32,
+        direction: SceneDirection,
+        signal: &str,
+        mode: ButtonMode,
+    ) {
+        self.spawn_button(Vec2::new(x, y), direction, signal, mode);
     }
 
-    pub fn spawn_button(&mut self, mut position: Vec2, direction: SceneDirection, mask: u32) {
+    pub fn spawn_button(
+        &mut self,
+        mut position: Vec2,
+        direction: SceneDirection,
+        signal: &str,
+        mode: ButtonMode,
+    ) -> EntityCommands<'w, 's, '_> {
         let size_y = direction.get_vec() * 55.0 * 3.0 / 20.0;
         let size_x = direction.get_perp().get_vec() * 55.0;
         let size = (size_y + size_x).abs();
@@ -68,8 +81,15 @@ impl<'w, 's, 'a, 'b> SceneBuilder<'w, 's, 'a, 'b> {
 
         position -= direction.get_vec() * (Self::CELL_SIZE - 1.0) * 0.5;
 
-        self.builder
-            .spawn()
+        let button_on = match mode {
+            ButtonMode::Timed { .. } => self
+                .materials
+                .add(Material::from(self.assets.load("images/button-on.png"))),
+            _ => self.button_on.clone(),
+        };
+
+        let mut button = self.builder.spawn();
+        button
             .insert_bundle(MaterialMesh2dBundle {
                 mesh: Mesh2dHandle(self.meshes.add(Quad::new(size).into())),
                 material: self.button_off.clone(),
@@ -78,11 +98,53 @@ impl<'w, 's, 'a, 'b> SceneBuilder<'w, 's, 'a, 'b> {
                 ..MaterialMesh2dBundle::default()
             })
             .insert(DoorButton {
-                mask,
+                signal: signal.to_string(),
                 direction,
-                button_on: self.button_on.clone(),
+                mode,
+                button_on,
                 button_off: self.button_off.clone(),
+                pressed: false,
                 enabled: false,
+                timer: 0.0,
+            });
+        button
+    }
+
+    pub fn spawn_pressure_plate_xy(
+        &mut self,
+        x: f32,
+        y: f32,
+        direction: SceneDirection,
+        signal: &str,
+        min_weight: f32,
+        max_weight: Option<f32>,
+    ) {
+        self.spawn_pressure_plate(Vec2::new(x, y), direction, signal, min_weight, max_weight);
+    }
+
+    pub fn spawn_pressure_plate(
+        &mut self,
+        position: Vec2,
+        direction: SceneDirection,
+        signal: &str,
+        min_weight: f32,
+        max_weight: Option<f32>,
+    ) {
+        let gauge = MaterialMesh2dBundle {
+            mesh: Mesh2dHandle(
+                self.meshes
+                    .add(Quad::new(Vec2::new(PressurePlate::GAUGE_WIDTH, 4.0)).into()),
+            ),
+            material: self.materials.add(Color::rgb_u8(120, 120, 120).into()),
+            // Drawn on the wall right under the plate
+            transform: Transform::from_xyz(0.0, -8.0, 0.1),
+            ..MaterialMesh2dBundle::default()
+        };
+
+        self.spawn_button(position, direction, signal, ButtonMode::Momentary)
+            .insert(PressurePlate::new(min_weight, max_weight))
+            .with_children(|plate| {
+                plate.spawn_bundle(gauge).insert(PressurePlateGauge);
             });
     }
 
@@ -91,8 +153,7 @@ impl<'w, 's, 'a, 'b> SceneBuilder<'w, 's, 'a, 'b> {
         position: Vec2,
         height: f32,
         direction: SceneDirection,
-        pressed_mask: u32,
-        not_pressed_mask: u32,
+        signal: &str,
     ) {
         self.builder
             .spawn()
@@ -107,9 +168,8 @@ impl<'w, 's, 'a, 'b> SceneBuilder<'w, 's, 'a, 'b> {
                     Door {
                         height: height * Self::CELL_SIZE,
                         direction,
-                        pressed_mask,
-                        not_pressed_mask,
                         progress: 0.0,
+                        signal: signal.to_string(),
                     },
                     self.meshes,
                     self.materials,
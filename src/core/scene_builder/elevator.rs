@@ -10,6 +10,7 @@ use crate::core::{collision_groups, Elevator, ElevatorType, Material, SceneBuild
 pub struct ElevatorBundle {
     pub elevator: Elevator,
     pub rigid_body: RigidBody,
+    pub velocity: Velocity,
     pub collider: Collider,
     #[bundle]
     pub mesh_bundle: MaterialMesh2dBundle<Material>,
@@ -33,7 +34,8 @@ impl ElevatorBundle {
 
         ElevatorBundle {
             elevator,
-            rigid_body: RigidBody::KinematicPositionBased,
+            rigid_body: RigidBody::KinematicVelocityBased,
+            velocity: Velocity::zero(),
             collider: Collider::cuboid(w * 0.5, h * 0.5),
             mesh_bundle: MaterialMesh2dBundle {
                 mesh: Mesh2dHandle(meshes.add(Quad::new(Vec2::new(w, h)).into())),
@@ -54,11 +56,13 @@ impl<'w, 's, 'a, 'b> SceneBuilder<'w, 's, 'a, 'b> {
         end_x: f32,
         end_y: f32,
         elevator_type: ElevatorType,
+        signal: Option<&str>,
     ) -> EntityCommands<'w, 's, '_> {
         self.spawn_elevator(
             Vec2::new(start_x, start_y),
             Vec2::new(end_x, end_y),
             elevator_type,
+            signal,
         )
     }
 
@@ -66,15 +70,27 @@ impl<'w, 's, 'a, 'b> SceneBuilder<'w, 's, 'a, 'b> {
         &mut self,
         mut start: Vec2,
         mut end: Vec2,
-        elevator_type: ElevatorType,
+        mut elevator_type: ElevatorType,
+        signal: Option<&str>,
     ) -> EntityCommands<'w, 's, '_> {
         start *= SceneBuilder::CELL_SIZE;
         end *= SceneBuilder::CELL_SIZE;
+        if let ElevatorType::Path {
+            waypoints, speed, ..
+        } = &mut elevator_type
+        {
+            *speed *= SceneBuilder::CELL_SIZE;
+            for waypoint in waypoints.iter_mut() {
+                waypoint.position *= SceneBuilder::CELL_SIZE;
+                waypoint.speed *= SceneBuilder::CELL_SIZE;
+            }
+        }
         self.builder.spawn_bundle(ElevatorBundle::new(
             Elevator {
                 start,
                 end,
                 elevator_type,
+                signal: signal.map(str::to_string),
             },
             self.meshes,
             self.materials,
@@ -0,0 +1,55 @@
+use bevy::prelude::shape::Quad;
+use bevy::prelude::*;
+use bevy::sprite::{MaterialMesh2dBundle, Mesh2dHandle};
+use bevy_rapier2d::prelude::*;
+
+use crate::core::{collision_groups, GravityZone, SceneBuilder, GRAVITY_FORCE};
+use crate::utils::SceneDirection;
+
+impl<'w, 's, 'a, 'b> SceneBuilder<'w, 's, 'a, 'b> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn spawn_gravity_zone_from_to_xy(
+        &mut self,
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+        direction: SceneDirection,
+        strength: f32,
+    ) {
+        self.spawn_gravity_zone_from_to(
+            Vec2::new(left, bottom),
+            Vec2::new(right, top),
+            direction,
+            strength,
+        );
+    }
+
+    /// Players and boxes inside fall towards `direction`, `strength` times as fast as usual.
+    pub fn spawn_gravity_zone_from_to(
+        &mut self,
+        mut from: Vec2,
+        mut to: Vec2,
+        direction: SceneDirection,
+        strength: f32,
+    ) {
+        from *= Self::CELL_SIZE;
+        to *= Self::CELL_SIZE;
+        let size = from.max(to) - from.min(to);
+        let translation = (from + to) * 0.5;
+
+        self.builder
+            .spawn_bundle(MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(self.meshes.add(Quad::new(size).into())),
+                material: self.materials.add(Color::rgba(0.5, 0.3, 1.0, 0.06).into()),
+                transform: Transform::from_xyz(translation.x, translation.y, Self::HINT_DEPTH),
+                ..default()
+            })
+            .insert(Collider::cuboid(size.x * 0.5, size.y * 0.5))
+            .insert(Sensor)
+            .insert(collision_groups::GRAVITY_ZONE)
+            .insert(GravityZone {
+                gravity: direction.get_vec() * strength * GRAVITY_FORCE,
+            });
+    }
+}
@@ -17,9 +17,12 @@ use super::BackgroundMusic;
 
 mod boundaries;
 mod combobox;
+mod dispenser;
 mod door;
 mod elevator;
+mod gravity;
 mod player;
+mod signal;
 mod spawn_point;
 mod wall;
 
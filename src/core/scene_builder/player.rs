@@ -6,8 +6,8 @@ use bevy_rapier2d::prelude::*;
 use post_processing::PointLight2d;
 
 use crate::core::{
-    collision_groups, material_from_texture_and_emissive, Material, Player, PlayerIndex,
-    PlayerType, SceneBuilder,
+    collision_groups, material_from_texture_and_emissive, BodyGravity, Material, Player,
+    PlayerIndex, PlayerType, SceneBuilder,
 };
 
 fn create_quad(half_size: Vec2, state: u32, num_states: u32, rotation: u32) -> Mesh {
@@ -86,6 +86,7 @@ pub struct PlayerBundle {
     transform: TransformBundle,
     point_light: PointLight2d,
     density: ColliderMassProperties,
+    gravity: BodyGravity,
 }
 
 impl PlayerBundle {
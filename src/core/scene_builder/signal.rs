@@ -0,0 +1,69 @@
+use bevy::prelude::shape::Quad;
+use bevy::prelude::*;
+use bevy::sprite::{MaterialMesh2dBundle, Mesh2dHandle};
+use post_processing::PointLight2d;
+
+use crate::core::{AreaSensor, SceneBuilder, SignalLight};
+
+impl<'w, 's, 'a, 'b> SceneBuilder<'w, 's, 'a, 'b> {
+    pub fn spawn_sensor_from_to_xy(
+        &mut self,
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+        signal: &str,
+    ) {
+        self.spawn_sensor_from_to(Vec2::new(left, bottom), Vec2::new(right, top), signal);
+    }
+
+    pub fn spawn_sensor_from_to(&mut self, mut from: Vec2, mut to: Vec2, signal: &str) {
+        from *= Self::CELL_SIZE;
+        to *= Self::CELL_SIZE;
+        let size = from.max(to) - from.min(to);
+        let translation = (from + to) * 0.5;
+
+        self.builder
+            .spawn_bundle(MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(self.meshes.add(Quad::new(size).into())),
+                // Barely visible, players should notice the area but not mistake it for a wall
+                material: self.materials.add(Color::rgba(1.0, 1.0, 1.0, 0.04).into()),
+                transform: Transform::from_xyz(translation.x, translation.y, Self::HINT_DEPTH),
+                ..default()
+            })
+            .insert(AreaSensor {
+                size,
+                signal: signal.to_string(),
+                enabled: false,
+            });
+    }
+
+    pub fn spawn_light_xy(
+        &mut self,
+        x: f32,
+        y: f32,
+        color: Color,
+        radius: f32,
+        signal: Option<&str>,
+    ) {
+        self.spawn_light(Vec2::new(x, y), color, radius, signal);
+    }
+
+    pub fn spawn_light(&mut self, position: Vec2, color: Color, radius: f32, signal: Option<&str>) {
+        let position = position * Self::CELL_SIZE;
+        let radius = radius * Self::CELL_SIZE;
+
+        self.builder
+            .spawn_bundle(MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(self.meshes.add(Quad::new(Vec2::splat(16.0)).into())),
+                material: self.materials.add(color.into()),
+                transform: Transform::from_xyz(position.x, position.y, Self::WALL_DEPTH),
+                ..default()
+            })
+            .insert(PointLight2d { radius, color })
+            .insert(SignalLight {
+                radius,
+                signal: signal.map(str::to_string),
+            });
+    }
+}
@@ -1,4 +1,4 @@
-use crate::core::{PlayerIndex, SceneBuilder};
+use crate::core::{CameraFocus, PlayerIndex, SceneBuilder};
 use bevy::prelude::shape::Quad;
 use bevy::prelude::*;
 use bevy::sprite::{MaterialMesh2dBundle, Mesh2dHandle};
@@ -9,7 +9,10 @@ pub struct SpawnPoint {
 }
 
 #[derive(Debug, Clone, Component)]
-pub struct FinishPoint;
+pub struct FinishPoint {
+    /// Players only finish while this signal is on.
+    pub signal: Option<String>,
+}
 
 #[derive(Debug, Clone, Component)]
 pub struct FinishPointArrow;
@@ -31,14 +34,19 @@ impl<'w, 's, 'a, 'b> SceneBuilder<'w, 's, 'a, 'b> {
             .insert_bundle(VisibilityBundle::default());
     }
 
-    pub fn set_finish_point_xy(&mut self, x: f32, y: f32) {
-        self.set_finish_point(Vec2::new(x, y));
+    pub fn set_finish_point_xy(&mut self, x: f32, y: f32, signal: Option<&str>) {
+        self.set_finish_point(Vec2::new(x, y), signal);
     }
 
-    pub fn set_finish_point(&mut self, position: Vec2) {
-        self.builder
-            .spawn()
-            .insert(FinishPoint)
+    pub fn set_finish_point(&mut self, position: Vec2, signal: Option<&str>) {
+        let mut finish = self.builder.spawn();
+        if self.boundaries.frame_finish {
+            finish.insert(CameraFocus);
+        }
+        finish
+            .insert(FinishPoint {
+                signal: signal.map(str::to_string),
+            })
             .insert_bundle(MaterialMesh2dBundle {
                 mesh: Mesh2dHandle(self.meshes.add(Quad::new(Vec2::new(200.0, 200.0)).into())),
                 material: self
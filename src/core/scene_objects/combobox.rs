@@ -1,15 +1,26 @@
 use bevy::math::Vec3Swizzles;
 use bevy::prelude::*;
-use bevy_kira_audio::{Audio, AudioControl};
 use bevy_rapier2d::prelude::*;
 
 use bevy_rapier2d::rapier::prelude::QueryFilterFlags;
-
-use crate::core::{collision_groups, GRAVITY_FORCE};
-use crate::core::{ComboboxBundle, Material};
+use serde::{Deserialize, Serialize};
+
+use crate::core::collision_groups;
+use crate::core::{
+    BoxKind, ComboboxBundle, ComboboxMerged, ComboboxUndone, Material, MergeRecipes, SimulationApp,
+    FIXED_DT,
+};
+use crate::levels::Rewind;
 use crate::states::LevelState;
+use crate::utils::SpatialHash;
+
+/// Boxes merge when they are closer than this many times their sizes added together, on
+/// both axes.
+const MERGE_DISTANCE: f32 = 0.52;
 
-#[derive(Clone, Debug, PartialEq)]
+/// `Boots` are worn by the player who touches them, who then falls the way the box does, see
+/// [`crate::core::GravityBoots`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ComboboxType {
     Standard { group: u32 },
     Buff(f32),
@@ -17,6 +28,45 @@ pub enum ComboboxType {
     Direction { direction: Vec2 },
     Gravity,
     Lamp { color: Color },
+    Split(u32),
+    Boots,
+}
+
+impl ComboboxType {
+    /// Image drawn on top of the box, relative to the assets folder.
+    pub fn overlay(&self) -> Option<&'static str> {
+        match *self {
+            ComboboxType::Undo => Some("images/overlay-undo.png"),
+            ComboboxType::Direction { direction } => {
+                if direction.y > 0.5 {
+                    Some("images/overlay-up.png")
+                } else if direction.y < -0.5 {
+                    Some("images/overlay-down.png")
+                } else if direction.x < -0.5 {
+                    Some("images/overlay-left.png")
+                } else {
+                    Some("images/overlay-right.png")
+                }
+            }
+            ComboboxType::Buff(buff) => [
+                (2.0, "images/overlay-x2.png"),
+                (3.0, "images/overlay-x3.png"),
+                (4.0, "images/overlay-x4.png"),
+                (9.0, "images/overlay-x9.png"),
+            ]
+            .into_iter()
+            .find(|(value, _)| (buff - value).abs() < 0.1)
+            .map(|(_, path)| path),
+            ComboboxType::Gravity => Some("images/overlay-gravity.png"),
+            ComboboxType::Split(pieces) => match pieces {
+                2 => Some("images/overlay-split2.png"),
+                3 => Some("images/overlay-split3.png"),
+                4 => Some("images/overlay-split4.png"),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Component)]
@@ -77,11 +127,14 @@ impl ComboboxState {
     }
 }
 
-#[derive(Component, Clone, Debug)]
+#[derive(Component, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Combobox {
     pub weight: f32,
     pub box_type: ComboboxType,
+    /// Boxes an undo box splits this one back into, with their offsets from its position.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub combined_from: Vec<(Combobox, Vec2)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub local_gravity: Option<Vec2>,
 }
 
@@ -101,116 +154,42 @@ impl Combobox {
         self.weight.sqrt() * Self::DEFAULT_SIZE
     }
 
+    /// Number of pieces the box was split into, each of them needs the others to be undone.
+    pub fn split_pieces(&self) -> usize {
+        self.combined_from
+            .iter()
+            .find_map(|(combobox, _)| match combobox.box_type {
+                ComboboxType::Split(pieces) => Some(pieces.max(1) as usize),
+                _ => None,
+            })
+            .unwrap_or(1)
+    }
+
+    /// Whether both boxes are pieces of equal boxes split the same way. Such pieces can't be
+    /// told apart, any of them joins back together.
+    pub fn is_piece_like(&self, other: &Combobox) -> bool {
+        self.weight == other.weight
+            && self.box_type == other.box_type
+            && self.local_gravity == other.local_gravity
+            && self.combined_from.len() == other.combined_from.len()
+            && self
+                .combined_from
+                .iter()
+                .zip(&other.combined_from)
+                .all(|((a, _), (b, _))| a == b)
+    }
+
+    /// Boxes that replace `first` and `second` when they touch, `None` if they don't combine.
+    /// Undo boxes always split the other box, everything else follows the `recipes`. A piece
+    /// of a split box is only undone together with the other pieces, see [`Self::split_pieces`].
     pub fn merge(
         first: &Combobox,
         first_pos: Vec2,
         second: &Combobox,
         second_pos: Vec2,
+        recipes: &MergeRecipes,
     ) -> Option<Vec<(Combobox, Vec2)>> {
-        let center = (first_pos * first.world_size() + second_pos * second.world_size())
-            / (first.world_size() + second.world_size());
-        let first_offset = first_pos - center;
-        let second_offset = second_pos - center;
-
         match (&first.box_type, &second.box_type) {
-            (
-                ComboboxType::Standard { group: group1 },
-                ComboboxType::Standard { group: group2 },
-            ) => {
-                if group1 != group2 {
-                    return None;
-                }
-
-                let mut gravity = None;
-                if let Some(first_gravity) = first.local_gravity {
-                    if let Some(second_gravity) = second.local_gravity {
-                        if first_gravity == second_gravity {
-                            gravity = Some(first_gravity.clone());
-                        }
-                    }
-                }
-
-                let big_box = Combobox {
-                    weight: first.weight + second.weight,
-                    box_type: first.box_type.clone(),
-                    combined_from: vec![
-                        (first.clone(), first_offset),
-                        (second.clone(), second_offset),
-                    ],
-                    local_gravity: gravity,
-                };
-
-                return Some(vec![(big_box, center)]);
-            }
-            (ComboboxType::Lamp { color: color1 }, ComboboxType::Lamp { color: color2 }) => {
-                if color1 != color2 {
-                    return None;
-                }
-
-                let mut gravity = None;
-                if let Some(first_gravity) = first.local_gravity {
-                    if let Some(second_gravity) = second.local_gravity {
-                        if first_gravity == second_gravity {
-                            gravity = Some(first_gravity.clone());
-                        }
-                    }
-                }
-
-                let big_box = Combobox {
-                    weight: first.weight + second.weight,
-                    box_type: ComboboxType::Lamp {
-                        color: color1.clone(),
-                    },
-                    combined_from: vec![
-                        (first.clone(), first_offset),
-                        (second.clone(), second_offset),
-                    ],
-                    local_gravity: gravity,
-                };
-
-                return Some(vec![(big_box, center)]);
-            }
-            (
-                ComboboxType::Buff(buff),
-                ComboboxType::Standard { .. } | ComboboxType::Lamp { .. },
-            ) => {
-                let buffed_box = Combobox {
-                    weight: second.weight * buff,
-                    box_type: second.box_type.clone(),
-                    combined_from: vec![
-                        (first.clone(), Vec2::ZERO),
-                        (second.clone(), second_pos - first_pos),
-                    ],
-                    local_gravity: second.local_gravity,
-                };
-                return Some(vec![(buffed_box, second_pos)]);
-            }
-            (ComboboxType::Direction { direction }, ComboboxType::Standard { .. } | ComboboxType::Lamp { .. }) => {
-                let direction_box = Combobox {
-                    weight: second.weight,
-                    box_type: second.box_type.clone(),
-                    combined_from: vec![
-                        (first.clone(), first_pos - second_pos),
-                        (second.clone(), Vec2::ZERO),
-                    ],
-                    local_gravity: Some(direction.clone()),
-                };
-
-                return Some(vec![(direction_box, second_pos)]);
-            }
-            (ComboboxType::Gravity, ComboboxType::Direction { direction }) => {
-                let gravity_box = Combobox {
-                    weight: first.weight,
-                    box_type: first.box_type.clone(),
-                    combined_from: vec![
-                        (first.clone(), Vec2::ZERO),
-                        (second.clone(), second_pos - first_pos),
-                    ],
-                    local_gravity: Some(direction.clone()),
-                };
-
-                return Some(vec![(gravity_box, first_pos)]);
-            }
             (ComboboxType::Undo, _) => {
                 if second.combined_from.len() == 0 {
                     return None;
@@ -235,13 +214,11 @@ impl Combobox {
                         .collect(),
                 );
             }
-            (_, ComboboxType::Undo)
-            | (ComboboxType::Standard { .. } | ComboboxType::Lamp { .. }, ComboboxType::Buff(_))
-            | (ComboboxType::Standard { .. } | ComboboxType::Lamp {..}, ComboboxType::Direction { .. })
-            | (ComboboxType::Direction { .. }, ComboboxType::Gravity) => {
-                Self::merge(second, second_pos, first, first_pos)
-            }
-            (_, _) => None,
+            (_, ComboboxType::Undo) => Self::merge(second, second_pos, first, first_pos, recipes),
+            _ => match recipes.find(first, second)? {
+                (recipe, false) => Some(recipe.apply(first, first_pos, second, second_pos)),
+                (recipe, true) => Some(recipe.apply(second, second_pos, first, first_pos)),
+            },
         }
     }
 }
@@ -250,14 +227,12 @@ pub struct ComboboxPlugin;
 
 impl Plugin for ComboboxPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system_set(
+        app.add_simulation_system_set(
             SystemSet::on_update(LevelState::Level)
                 .with_system(merge)
                 .with_system(animation)
                 .with_system(pushback)
-                .with_system(despawn)
-                .with_system(change_direction)
-                .with_system(change_gravity),
+                .with_system(despawn),
         );
     }
 }
@@ -265,13 +240,12 @@ impl Plugin for ComboboxPlugin {
 fn animation(
     mut commands: Commands,
     mut comboboxes: Query<(Entity, &mut ComboboxState, &mut Transform)>,
-    time: Res<Time>,
 ) {
     for (entity, mut combobox_state, mut transform) in comboboxes.iter_mut() {
         let mut new_state = None;
 
         if let ComboboxState::SpawningAnimation(animation_time) = &mut *combobox_state {
-            *animation_time += time.delta_seconds();
+            *animation_time += FIXED_DT;
 
             if *animation_time >= ComboboxState::SPAWN_TIME {
                 new_state = Some(ComboboxState::Normal);
@@ -280,7 +254,7 @@ fn animation(
             }
         }
         if let ComboboxState::DespawningAnimation(animation_time) = &mut *combobox_state {
-            *animation_time += time.delta_seconds();
+            *animation_time += FIXED_DT;
 
             if *animation_time >= ComboboxState::DESPAWN_TIME {
                 new_state = Some(ComboboxState::Despawned)
@@ -304,7 +278,6 @@ fn pushback(
         &RigidBody,
     )>,
     context: ResMut<RapierContext>,
-    time: Res<Time>,
 ) {
     for (entity, combobox, combobox_state, mut transform) in comboboxes.iter_mut() {
         if let ComboboxState::SpawningAnimation(_) = *combobox_state {
@@ -318,7 +291,8 @@ fn pushback(
             let half_size = combobox.world_size() * 0.5 * combobox_state.get_scale_ahead(0.1);
             let half_size2 = combobox.world_size() * 0.5 * combobox_state.get_scale_ahead(1.0);
 
-            let filter: QueryFilter = QueryFilterFlags::EXCLUDE_KINEMATIC.into();
+            let filter: QueryFilter =
+                (QueryFilterFlags::EXCLUDE_KINEMATIC | QueryFilterFlags::EXCLUDE_SENSORS).into();
 
             for offset in offsets {
                 for dir in directions {
@@ -335,10 +309,8 @@ fn pushback(
                             {
                                 let depth = (half_size2 * 1.01 - e.toi).clamp(0.0, 50.0);
                                 let velocity = dir.dot(velocity.linvel);
-                                impulse.impulse +=
-                                    dir * time.delta_seconds() * depth * mass.0.mass * 300.0;
-                                impulse.impulse -=
-                                    dir * time.delta_seconds() * velocity * mass.0.mass * 5.0;
+                                impulse.impulse += dir * FIXED_DT * depth * mass.0.mass * 300.0;
+                                impulse.impulse -= dir * FIXED_DT * velocity * mass.0.mass * 5.0;
                             } else {
                                 let depth = (half_size * 1.01 - e.toi).clamp(0.0, 50.0);
                                 let offset = dir * depth * 0.3;
@@ -353,50 +325,6 @@ fn pushback(
     }
 }
 
-fn change_direction(
-    comboboxes: Query<(&Combobox, &RapierRigidBodyHandle)>,
-    mut context: ResMut<RapierContext>,
-) {
-    for (combobox, handle) in comboboxes.iter() {
-        if let Some(mut gravity) = combobox.local_gravity {
-            if let Some(rb) = context.bodies.get_mut(handle.0) {
-                gravity = gravity * GRAVITY_FORCE * rb.mass();
-                rb.set_gravity_scale(0., true);
-                rb.reset_forces(true);
-                rb.add_force(Vec2::new(gravity.x, gravity.y).into(), true);
-            }
-        }
-    }
-}
-
-fn change_gravity(
-    comboboxes: Query<(&Combobox, &ComboboxState)>,
-    rigidbodies: Query<&RapierRigidBodyHandle>,
-    mut config: ResMut<RapierConfiguration>,
-    mut context: ResMut<RapierContext>,
-    mut prev_gravity: Local<Vec2>,
-) {
-    config.gravity = Vec2::NEG_Y * GRAVITY_FORCE;
-
-    for (combobox, state) in comboboxes.iter() {
-        if combobox.box_type == ComboboxType::Gravity && *state == ComboboxState::Normal {
-            if let Some(gravity) = combobox.local_gravity {
-                config.gravity = gravity * GRAVITY_FORCE;
-            }
-        }
-    }
-
-    if (*prev_gravity - config.gravity).length() > 0.1 {
-        for handle in rigidbodies.iter() {
-            if let Some(rb) = context.bodies.get_mut(handle.0) {
-                rb.wake_up(true);
-            }
-        }
-    }
-
-    *prev_gravity = config.gravity;
-}
-
 fn despawn(mut commands: Commands, mut comboboxes: Query<(Entity, &ComboboxState)>) {
     for (entity, combobox_state) in comboboxes.iter_mut() {
         if *combobox_state == ComboboxState::Despawned {
@@ -411,53 +339,135 @@ fn merge(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<Material>>,
     mut assets: ResMut<AssetServer>,
-    audio: Res<Audio>,
+    rewind: Res<Rewind>,
+    recipes: Res<MergeRecipes>,
+    mut merged: EventWriter<ComboboxMerged>,
+    mut undone: EventWriter<ComboboxUndone>,
 ) {
-    'outer: for (i, (a, parent, combobox_a, transform_a, state_a)) in comboboxes.iter().enumerate()
-    {
-        for (j, (b, _, combobox_b, transform_b, state_b)) in comboboxes.iter().enumerate() {
-            if i >= j {
-                continue;
-            }
+    // A snapshot from right before a merge would merge again
+    if rewind.is_rewinding() {
+        return;
+    }
 
-            if *state_a != ComboboxState::Normal || *state_b != ComboboxState::Normal {
+    // Entities are allocated in the same order on every run, unlike the query order
+    let mut boxes: Vec<_> = comboboxes
+        .iter()
+        .filter(|(.., state)| **state == ComboboxState::Normal)
+        .collect();
+    boxes.sort_by_key(|(entity, ..)| *entity);
+
+    let mut grid = SpatialHash::new(Combobox::DEFAULT_SIZE);
+    for (i, (_, _, combobox, transform, _)) in boxes.iter().enumerate() {
+        let reach = Vec2::splat(combobox.world_size() * MERGE_DISTANCE);
+        let position = transform.translation.xy();
+        grid.insert(position - reach, position + reach, i);
+    }
+
+    // Every box takes part in at most one merge per frame
+    let mut used = vec![false; boxes.len()];
+
+    for (i, j) in grid.pairs() {
+        if used[i] || used[j] {
+            continue;
+        }
+
+        let (a, parent, combobox_a, transform_a, _) = boxes[i];
+        let (b, _, combobox_b, transform_b, _) = boxes[j];
+
+        let pos_a = transform_a.translation.xy();
+        let pos_b = transform_b.translation.xy();
+
+        if (pos_a - pos_b).abs().max_element()
+            >= (combobox_a.world_size() + combobox_b.world_size()) * MERGE_DISTANCE
+        {
+            continue;
+        }
+
+        let merge = match Combobox::merge(combobox_a, pos_a, combobox_b, pos_b, &recipes) {
+            Some(merge) => merge,
+            None => continue,
+        };
+
+        // Undoing a piece of a split box joins all the pieces, the closest ones first
+        let mut pieces = vec![];
+        let undone_box = match (&combobox_a.box_type, &combobox_b.box_type) {
+            (ComboboxType::Undo, _) => Some((j, combobox_b, pos_b)),
+            (_, ComboboxType::Undo) => Some((i, combobox_a, pos_a)),
+            _ => None,
+        };
+        if let Some((k, piece, piece_pos)) = undone_box {
+            let mut others: Vec<_> = boxes
+                .iter()
+                .enumerate()
+                .filter(|(l, (_, _, other, ..))| {
+                    *l != k && !used[*l] && *l != i && *l != j && piece.is_piece_like(other)
+                })
+                .map(|(l, (.., transform, _))| (l, transform.translation.xy().distance(piece_pos)))
+                .collect();
+            others.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+            if others.len() + 1 < piece.split_pieces() {
                 continue;
             }
+            pieces = others
+                .into_iter()
+                .take(piece.split_pieces() - 1)
+                .map(|(l, _)| l)
+                .collect();
+        }
 
-            let pos_a = transform_a.translation.xy();
-            let pos_b = transform_b.translation.xy();
-
-            if (pos_a - pos_b).abs().max_element()
-                < (combobox_a.world_size() + combobox_b.world_size()) * 0.52
-            {
-                if let Some(merge) = Combobox::merge(combobox_a, pos_a, combobox_b, pos_b) {
-                    for (combobox_new, pos_new) in merge {
-                        let id = commands
-                            .spawn_bundle(ComboboxBundle::new(
-                                combobox_new,
-                                pos_new,
-                                &mut meshes,
-                                &mut materials,
-                                &mut assets,
-                            ))
-                            .id();
-                        commands.entity(parent.get()).add_child(id);
-
-                        audio.play(assets.load("audio/box_join.ogg"));
-                    }
-                    commands
-                        .entity(a)
-                        .insert(RigidBody::KinematicPositionBased)
-                        .insert(ComboboxState::DespawningAnimation(0.0))
-                        .insert(CollisionGroups::new(0, 0));
-                    commands
-                        .entity(b)
-                        .insert(RigidBody::KinematicPositionBased)
-                        .insert(ComboboxState::DespawningAnimation(0.0))
-                        .insert(CollisionGroups::new(0, 0));
-                    break 'outer;
-                }
-            }
+        used[i] = true;
+        used[j] = true;
+        for l in pieces.iter() {
+            used[*l] = true;
+        }
+        let pieces: Vec<Entity> = pieces.into_iter().map(|l| boxes[l].0).collect();
+
+        let kind = BoxKind::of(&merge[0].0.box_type);
+        let mut outputs = vec![];
+
+        for (combobox_new, pos_new) in merge {
+            let id = commands
+                .spawn_bundle(ComboboxBundle::new(
+                    combobox_new,
+                    pos_new,
+                    &mut meshes,
+                    &mut materials,
+                    &mut assets,
+                ))
+                .id();
+            commands.entity(parent.get()).add_child(id);
+            outputs.push(id);
+        }
+
+        if combobox_a.box_type == ComboboxType::Undo {
+            undone.send(ComboboxUndone {
+                undo: a,
+                combobox: b,
+                pieces: pieces.clone(),
+                outputs,
+            });
+        } else if combobox_b.box_type == ComboboxType::Undo {
+            undone.send(ComboboxUndone {
+                undo: b,
+                combobox: a,
+                pieces: pieces.clone(),
+                outputs,
+            });
+        } else {
+            merged.send(ComboboxMerged {
+                inputs: [a, b],
+                outputs,
+                kind,
+            });
+        }
+
+        for entity in [a, b].into_iter().chain(pieces) {
+            commands
+                .entity(entity)
+                .insert(RigidBody::KinematicPositionBased)
+                .insert(ComboboxState::DespawningAnimation(0.0))
+                .insert(CollisionGroups::new(0, 0));
         }
     }
 }
@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+
+use crate::core::{Circuit, Combobox, ComboboxBundle, Material, SignalSystem, SimulationApp};
+use crate::states::LevelState;
+
+/// Drops a copy of `combobox` every time its signal turns on.
+#[derive(Component, Debug, Clone)]
+pub struct BoxDispenser {
+    pub combobox: Combobox,
+    pub signal: String,
+}
+
+pub struct DispenserPlugin;
+
+impl Plugin for DispenserPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_simulation_system_set(
+            SystemSet::on_update(LevelState::Level).with_system(dispense.after(SignalSystem)),
+        );
+    }
+}
+
+fn dispense(
+    mut commands: Commands,
+    dispensers: Query<(&Parent, &Transform, &BoxDispenser)>,
+    circuit: Res<Circuit>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<Material>>,
+    mut assets: ResMut<AssetServer>,
+) {
+    for (parent, transform, dispenser) in dispensers.iter() {
+        if circuit.turned_on(&dispenser.signal) {
+            let id = commands
+                .spawn_bundle(ComboboxBundle::new(
+                    dispenser.combobox.clone(),
+                    transform.translation.truncate(),
+                    &mut meshes,
+                    &mut materials,
+                    &mut assets,
+                ))
+                .id();
+            commands.entity(parent.get()).add_child(id);
+        }
+    }
+}
@@ -1,66 +1,299 @@
-use crate::core::collision_groups::ELEVATOR_I;
-use crate::core::Material;
+use crate::core::{
+    crushes, drive, ButtonPressed, ButtonReleased, Circuit, Combobox, ComboboxState, DoorClosed,
+    DoorOpened, KinematicBodyQuery, KinematicSystem, Material, Player, SignalSystem, SimulationApp,
+    FIXED_DT,
+};
+use crate::levels::Rewind;
 use crate::states::LevelState;
 use crate::utils::SceneDirection;
 use bevy::prelude::*;
 use bevy_rapier2d::plugin::RapierContext;
 use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
 
 #[derive(Component, Debug, Clone)]
 pub struct Door {
     pub height: f32,
     pub direction: SceneDirection,
     pub progress: f32,
-    pub pressed_mask: u32,
-    pub not_pressed_mask: u32,
+    /// Opens while this signal is on.
+    pub signal: String,
+}
+
+impl Door {
+    /// Where the door is at `progress`, relative to where it is closed.
+    pub fn offset(&self, progress: f32) -> Vec2 {
+        self.direction.get_vec() * progress * self.height
+    }
 }
 
 #[derive(Component, Debug, Clone)]
 pub struct DoorButton {
-    pub mask: u32,
+    pub signal: String,
     pub direction: SceneDirection,
+    pub mode: ButtonMode,
     pub button_off: Handle<Material>,
+    /// Timed buttons have one of their own, it fades while the time runs out.
     pub button_on: Handle<Material>,
+    /// Whether something is on the button right now.
+    pub pressed: bool,
+    /// Whether the signal is on, which depends on the mode.
     pub enabled: bool,
+    /// Seconds a timed button stays on.
+    pub timer: f32,
+}
+
+/// How a [`DoorButton`] turns its signal on.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum ButtonMode {
+    /// On while pressed.
+    #[default]
+    Momentary,
+    /// Every press flips it.
+    Toggle,
+    /// Stays on once pressed, until the `reset` signal turns on.
+    Latch {
+        #[serde(default)]
+        reset: Option<String>,
+    },
+    /// Stays on for `seconds` after being released.
+    Timed { seconds: f32 },
+}
+
+impl ButtonMode {
+    pub fn is_momentary(&self) -> bool {
+        *self == ButtonMode::Momentary
+    }
+}
+
+/// A [`DoorButton`] that is only pressed while the weight resting on it is in range.
+#[derive(Component, Debug, Clone)]
+pub struct PressurePlate {
+    pub min_weight: f32,
+    pub max_weight: Option<f32>,
+    /// Weight of the boxes and players on the plate, including the ones stacked on top.
+    pub load: f32,
+}
+
+impl PressurePlate {
+    /// A player weighs as much as a box of weight 1.
+    pub const PLAYER_WEIGHT: f32 = 1.0;
+    pub const GAUGE_WIDTH: f32 = 50.0;
+
+    pub fn new(min_weight: f32, max_weight: Option<f32>) -> PressurePlate {
+        PressurePlate {
+            min_weight,
+            max_weight,
+            load: 0.0,
+        }
+    }
+
+    pub fn accepts(&self, load: f32) -> bool {
+        load >= self.min_weight && self.max_weight.is_none_or(|max| load <= max)
+    }
 }
 
+/// Bar under a [`PressurePlate`], filled up to the minimum weight.
+#[derive(Component)]
+pub struct PressurePlateGauge;
+
 pub struct DoorPlugin;
 
 impl Plugin for DoorPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system_set(SystemSet::on_update(LevelState::Level).with_system(update));
+        app.add_simulation_system_set(
+            SystemSet::on_update(LevelState::Level)
+                .with_system(update_buttons.before(SignalSystem))
+                .with_system(update_doors.after(SignalSystem).label(KinematicSystem))
+                .with_system(update_gauges.after(update_buttons))
+                .with_system(update_countdowns.after(update_buttons)),
+        );
     }
 }
 
-fn update(
+type BodyQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static GlobalTransform,
+        Option<&'static Combobox>,
+        Option<&'static ComboboxState>,
+        Option<&'static Player>,
+    ),
+>;
+
+/// Everything resting on the plate, found by following contacts away from it.
+fn plate_load(
+    position: Vec2,
+    direction: SceneDirection,
+    context: &RapierContext,
+    bodies: &BodyQuery,
+) -> f32 {
+    let up = direction.get_vec();
+    let area = (up * 5.0 + direction.get_perp().get_vec() * 25.0).abs();
+
+    let mut stack = vec![];
+    context.intersections_with_shape(
+        position + up * 5.0,
+        0.0,
+        &Collider::cuboid(area.x, area.y),
+        QueryFilter::new(),
+        |entity| {
+            stack.push(entity);
+            true
+        },
+    );
+
+    let mut counted = vec![];
+    let mut load = 0.0;
+
+    while let Some(entity) = stack.pop() {
+        if counted.contains(&entity) {
+            continue;
+        }
+
+        let (transform, weight) = match bodies.get(entity) {
+            Ok((transform, Some(combobox), Some(ComboboxState::Normal), _)) => {
+                (transform, combobox.weight)
+            }
+            Ok((transform, None, _, Some(_))) => (transform, PressurePlate::PLAYER_WEIGHT),
+            _ => continue,
+        };
+        counted.push(entity);
+        load += weight;
+
+        let level = transform.translation().truncate().dot(up);
+        for contact in context.contacts_with(entity) {
+            if !contact.has_any_active_contacts() {
+                continue;
+            }
+
+            let other = if contact.collider1() == entity {
+                contact.collider2()
+            } else {
+                contact.collider1()
+            };
+
+            if let Ok((other_transform, ..)) = bodies.get(other) {
+                if other_transform.translation().truncate().dot(up) > level + 1.0 {
+                    stack.push(other);
+                }
+            }
+        }
+    }
+
+    load
+}
+
+fn update_gauges(
+    plates: Query<(&PressurePlate, &Children)>,
+    mut gauges: Query<(&mut Transform, &Handle<Material>), With<PressurePlateGauge>>,
+    mut materials: ResMut<Assets<Material>>,
+) {
+    for (plate, children) in plates.iter() {
+        let fill = if plate.min_weight > 0.0 {
+            (plate.load / plate.min_weight).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+
+        let color = if plate.accepts(plate.load) {
+            Color::rgb_u8(103, 245, 124)
+        } else if plate.load > plate.min_weight {
+            Color::rgb_u8(232, 67, 56)
+        } else {
+            Color::rgb_u8(120, 120, 120)
+        };
+
+        for child in children.iter() {
+            if let Ok((mut transform, material)) = gauges.get_mut(*child) {
+                // Grows from the left end of the plate
+                transform.scale.x = fill.max(0.01);
+                transform.translation.x = -PressurePlate::GAUGE_WIDTH * 0.5 * (1.0 - fill);
+
+                if let Some(material) = materials.get_mut(material) {
+                    material.color = color;
+                }
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_buttons(
     mut commands: Commands,
-    mut doors: Query<(Entity, &mut Transform, &mut Door, &GlobalTransform)>,
-    mut buttons: Query<(Entity, &Transform, &mut DoorButton), Without<Door>>,
-    time: Res<Time>,
+    mut buttons: Query<(
+        Entity,
+        &Transform,
+        &mut DoorButton,
+        Option<&mut PressurePlate>,
+    )>,
+    bodies: BodyQuery,
+    circuit: Res<Circuit>,
     context: Res<RapierContext>,
-    materials: ResMut<Assets<Material>>,
+    rewind: Res<Rewind>,
+    mut pressed_events: EventWriter<ButtonPressed>,
+    mut released_events: EventWriter<ButtonReleased>,
 ) {
-    let mut pressed_buttons = 0;
-
-    for (entity, transform, mut button) in buttons.iter_mut() {
-        let enabled = context
-            .cast_ray(
-                transform.translation.truncate() + button.direction.get_perp().get_vec() * 15.0,
-                button.direction.get_vec(),
-                10.0,
-                true,
-                QueryFilter::new(),
-            )
-            .is_some()
-            || context
-                .cast_ray(
-                    transform.translation.truncate() - button.direction.get_perp().get_vec() * 15.0,
-                    button.direction.get_vec(),
-                    10.0,
-                    true,
-                    QueryFilter::new(),
-                )
-                .is_some();
+    // Restored with the circuit
+    if rewind.is_rewinding() {
+        return;
+    }
+
+    for (entity, transform, mut button, plate) in buttons.iter_mut() {
+        let pressed = match plate {
+            Some(mut plate) => {
+                plate.load = plate_load(
+                    transform.translation.truncate(),
+                    button.direction,
+                    &context,
+                    &bodies,
+                );
+                plate.accepts(plate.load)
+            }
+            None => {
+                context
+                    .cast_ray(
+                        transform.translation.truncate()
+                            + button.direction.get_perp().get_vec() * 15.0,
+                        button.direction.get_vec(),
+                        10.0,
+                        true,
+                        QueryFilter::new().exclude_sensors(),
+                    )
+                    .is_some()
+                    || context
+                        .cast_ray(
+                            transform.translation.truncate()
+                                - button.direction.get_perp().get_vec() * 15.0,
+                            button.direction.get_vec(),
+                            10.0,
+                            true,
+                            QueryFilter::new().exclude_sensors(),
+                        )
+                        .is_some()
+            }
+        };
+
+        // Reset signals are read as they were last frame
+        let enabled = match &button.mode {
+            ButtonMode::Momentary => pressed,
+            ButtonMode::Toggle if pressed && !button.pressed => !button.enabled,
+            ButtonMode::Toggle => button.enabled,
+            ButtonMode::Latch { reset } => {
+                !reset.as_deref().is_some_and(|reset| circuit.is_on(reset))
+                    && (button.enabled || pressed)
+            }
+            ButtonMode::Timed { seconds } => {
+                if pressed {
+                    button.timer = *seconds;
+                } else {
+                    button.timer = (button.timer - FIXED_DT).max(0.0);
+                }
+                button.timer > 0.0
+            }
+        };
+        button.pressed = pressed;
 
         if enabled != button.enabled {
             button.enabled = enabled;
@@ -69,55 +302,81 @@ fn update(
             } else {
                 button.button_off.clone()
             });
-        }
 
-        if enabled {
-            pressed_buttons |= button.mask;
+            if enabled {
+                pressed_events.send(ButtonPressed {
+                    button: entity,
+                    signal: button.signal.clone(),
+                });
+            } else {
+                released_events.send(ButtonReleased {
+                    button: entity,
+                    signal: button.signal.clone(),
+                });
+            }
         }
     }
+}
 
-    for (_entity, mut transform, mut door, g_transform) in doors.iter_mut() {
-        let mut opening = true;
-
-        if (door.pressed_mask & pressed_buttons) != door.pressed_mask {
-            opening = false;
+fn update_countdowns(buttons: Query<&DoorButton>, mut materials: ResMut<Assets<Material>>) {
+    for button in buttons.iter() {
+        if let ButtonMode::Timed { seconds } = button.mode {
+            if let Some(material) = materials.get_mut(&button.button_on) {
+                let left = if seconds > 0.0 {
+                    button.timer / seconds
+                } else {
+                    0.0
+                };
+                material.color = Color::WHITE * (0.3 + 0.7 * left);
+            }
         }
+    }
+}
 
-        if (door.not_pressed_mask & pressed_buttons) != 0 {
-            opening = false;
+#[allow(clippy::too_many_arguments)]
+fn update_doors(
+    mut doors: Query<(Entity, &mut Transform, &mut Velocity, &mut Door)>,
+    bodies: KinematicBodyQuery,
+    circuit: Res<Circuit>,
+    context: Res<RapierContext>,
+    config: Res<RapierConfiguration>,
+    rewind: Res<Rewind>,
+    mut opened: EventWriter<DoorOpened>,
+    mut closed: EventWriter<DoorClosed>,
+) {
+    for (entity, mut transform, mut velocity, mut door) in doors.iter_mut() {
+        // Only follows the progress rewinding restores
+        if rewind.is_rewinding() {
+            let target = door.offset(door.progress);
+            drive(&mut transform, &mut velocity, target, FIXED_DT, &config);
+            continue;
         }
 
-        if opening {
-            door.progress += time.delta_seconds();
+        let previous_progress = door.progress;
+
+        let direction = if circuit.is_on(&door.signal) {
+            1.0
         } else {
-            if context
-                .cast_ray(
-                    g_transform.translation().truncate()
-                        + door.direction.get_perp().get_vec() * 13.0,
-                    door.direction.get_opposite().get_vec(),
-                    door.height * 0.52,
-                    true,
-                    QueryFilter::new().groups(ELEVATOR_I),
-                )
-                .is_none()
-                && context
-                    .cast_ray(
-                        g_transform.translation().truncate()
-                            - door.direction.get_perp().get_vec() * 13.0,
-                        door.direction.get_opposite().get_vec(),
-                        door.height * 0.52,
-                        true,
-                        QueryFilter::new().groups(ELEVATOR_I),
-                    )
-                    .is_none()
-            {
-                door.progress -= time.delta_seconds();
-            }
+            -1.0
+        };
+        let progress = (door.progress + direction * FIXED_DT).clamp(0.0, 1.0);
+        let target = door.offset(progress);
+
+        let step = target - transform.translation.truncate();
+        if config.physics_pipeline_active && crushes(&context, &bodies, entity, step) {
+            velocity.linvel = Vec2::ZERO;
+            continue;
         }
-        door.progress = door.progress.clamp(0.0, 1.0);
 
-        let offset = door.direction.get_vec() * door.progress * door.height;
-        transform.translation.x = offset.x;
-        transform.translation.y = offset.y;
+        drive(&mut transform, &mut velocity, target, FIXED_DT, &config);
+        door.progress = progress;
+
+        if door.progress != previous_progress {
+            if door.progress == 1.0 {
+                opened.send(DoorOpened { door: entity });
+            } else if door.progress == 0.0 {
+                closed.send(DoorClosed { door: entity });
+            }
+        }
     }
 }
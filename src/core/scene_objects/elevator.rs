@@ -1,12 +1,143 @@
-use crate::core::collision_groups;
+use crate::core::{
+    crushes, drive, Circuit, KinematicBodyQuery, KinematicSystem, SignalSystem, SimulationApp,
+    FIXED_DT,
+};
+use crate::levels::Rewind;
 use crate::states::LevelState;
 use bevy::prelude::*;
 use bevy_rapier2d::plugin::RapierContext;
 use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ElevatorType {
+    /// Shuttles between `start` and `end`, a round trip takes `period` seconds.
     Loop { period: f32, current: f32 },
+    /// Heads to `end` while its signal is on. When it goes off the elevator stops where it is,
+    /// or goes back to `start` if it `returns`.
+    Triggered {
+        seconds: f32,
+        returns: bool,
+        progress: f32,
+    },
+    /// Goes from `start` through the `waypoints` to `end` and back the same way, moving at
+    /// `speed` on the last stretch to `end` and waiting `wait` seconds at both ends.
+    Path {
+        waypoints: Vec<Waypoint>,
+        speed: f32,
+        wait: f32,
+        current: f32,
+    },
+    /// Moves to `end` once its signal turns on, and stays there.
+    OneShot { seconds: f32, progress: f32 },
+}
+
+/// A stop on the way of an [`ElevatorType::Path`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Waypoint {
+    pub position: Vec2,
+    /// Speed on the stretch leading here, in the units of `position` per second.
+    pub speed: f32,
+    #[serde(default)]
+    pub wait: f32,
+}
+
+impl ElevatorType {
+    /// Moves the elevator along by `delta` seconds.
+    fn advance(&mut self, delta: f32, on: bool) {
+        match self {
+            ElevatorType::Loop { current, .. } | ElevatorType::Path { current, .. } => {
+                if on {
+                    *current += delta;
+                }
+            }
+            ElevatorType::Triggered {
+                seconds,
+                returns,
+                progress,
+            } => {
+                let direction = if on {
+                    1.0
+                } else if *returns {
+                    -1.0
+                } else {
+                    0.0
+                };
+                *progress = (*progress + direction * delta / *seconds).clamp(0.0, 1.0);
+            }
+            ElevatorType::OneShot { seconds, progress } => {
+                if on || *progress > 0.0 {
+                    *progress = (*progress + delta / *seconds).clamp(0.0, 1.0);
+                }
+            }
+        }
+    }
+
+    fn position(&self, start: Vec2, end: Vec2) -> Vec2 {
+        let t = match self {
+            ElevatorType::Loop { period, current } => {
+                let t = (*current / (*period * 0.5)) % 2.0;
+                let t = ((if t < 1.0 { t } else { 2.0 - t } - 0.5) * 1.2 + 0.5).clamp(0.0, 1.0);
+                t * t * (3.0 - 2.0 * t)
+            }
+            ElevatorType::Triggered { progress, .. } | ElevatorType::OneShot { progress, .. } => {
+                progress * progress * (3.0 - 2.0 * progress)
+            }
+            ElevatorType::Path {
+                waypoints,
+                speed,
+                wait,
+                current,
+            } => return path_position(start, end, waypoints, *speed, *wait, *current),
+        };
+        start * (1.0 - t) + end * t
+    }
+}
+
+fn path_position(
+    start: Vec2,
+    end: Vec2,
+    waypoints: &[Waypoint],
+    speed: f32,
+    wait: f32,
+    current: f32,
+) -> Vec2 {
+    // (position, speed on the way there, wait once there)
+    let stops: Vec<(Vec2, f32, f32)> = std::iter::once((start, speed, wait))
+        .chain(
+            waypoints
+                .iter()
+                .map(|waypoint| (waypoint.position, waypoint.speed, waypoint.wait)),
+        )
+        .chain(std::iter::once((end, speed, wait)))
+        .collect();
+
+    // (from, to, duration), waiting is moving from a stop to itself
+    let mut legs = vec![];
+    for pair in stops.windows(2) {
+        let ((from, _, _), (to, speed, wait)) = (pair[0], pair[1]);
+        legs.push((from, to, from.distance(to) / speed));
+        legs.push((to, to, wait));
+    }
+    for pair in stops.windows(2).rev() {
+        let ((to, _, wait), (from, speed, _)) = (pair[0], pair[1]);
+        legs.push((from, to, from.distance(to) / speed));
+        legs.push((to, to, wait));
+    }
+
+    let total: f32 = legs.iter().map(|(_, _, duration)| duration).sum();
+    if !(total > 0.0 && total.is_finite()) {
+        return start;
+    }
+
+    let mut t = current.rem_euclid(total);
+    for (from, to, duration) in legs {
+        if t < duration {
+            return from.lerp(to, t / duration);
+        }
+        t -= duration;
+    }
+    start
 }
 
 #[derive(Component, Debug, Clone)]
@@ -14,6 +145,9 @@ pub struct Elevator {
     pub start: Vec2,
     pub end: Vec2,
     pub elevator_type: ElevatorType,
+    /// Looping elevators only move while this signal is on, triggered and one-shot ones are
+    /// set off by it.
+    pub signal: Option<String>,
 }
 
 impl Elevator {
@@ -25,66 +159,45 @@ pub struct ElevatorPlugin;
 
 impl Plugin for ElevatorPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system_set(SystemSet::on_update(LevelState::Level).with_system(update));
+        app.add_simulation_system_set(
+            SystemSet::on_update(LevelState::Level)
+                .with_system(update.after(SignalSystem).label(KinematicSystem)),
+        );
     }
 }
 
 fn update(
-    mut elevators: Query<(Entity, &mut Transform, &mut Elevator)>,
-    time: Res<Time>,
+    mut elevators: Query<(Entity, &mut Transform, &mut Velocity, &mut Elevator)>,
+    bodies: KinematicBodyQuery,
+    circuit: Res<Circuit>,
     context: Res<RapierContext>,
+    config: Res<RapierConfiguration>,
+    rewind: Res<Rewind>,
 ) {
-    for (_entity, mut transform, mut elevator) in elevators.iter_mut() {
-        let mut anything_below = false;
-        let mut anything_and_interacts = false;
-
-        let intervals = 6;
-
-        let direction_down = (elevator.start - elevator.end).normalize();
-
-        for i in 0..intervals {
-            let origin = transform.translation.truncate()
-                + direction_down.perp()
-                    * Elevator::WIDTH
-                    * ((i as f32 / (intervals - 1) as f32) - 0.5) * 0.95;
-            let query_filter = QueryFilter::new().groups(collision_groups::ELEVATOR_I);
-
-            if let Some((_, v)) = context.cast_ray(
-                origin,
-                direction_down,
-                Elevator::HEIGHT * 0.7,
-                true,
-                query_filter,
-            ) {
-                anything_below = true;
-                if v < Elevator::HEIGHT * 0.51 {
-                    anything_and_interacts = true;
-                }
-                break;
-            }
+    for (entity, mut transform, mut velocity, mut elevator) in elevators.iter_mut() {
+        // Only follows the state rewinding restores
+        if rewind.is_rewinding() {
+            let target = elevator
+                .elevator_type
+                .position(elevator.start, elevator.end);
+            drive(&mut transform, &mut velocity, target, FIXED_DT, &config);
+            continue;
         }
 
-        match &mut elevator.elevator_type {
-            ElevatorType::Loop { period, current } => {
-                *current += time.delta_seconds();
-                let t = (*current / (*period * 0.5)) % 2.0;
+        let on = circuit.allows(elevator.signal.as_deref());
 
-                if t > 1.0 && anything_below {
-                    // Don't move!
-                    *current -= time.delta_seconds() * 1.0;
+        let mut moved = elevator.elevator_type.clone();
+        moved.advance(FIXED_DT, on);
+        let target = moved.position(elevator.start, elevator.end);
 
-                    if anything_and_interacts {
-                        *current -= time.delta_seconds() * 1.0;
-                    }
-                }
-                let t = (*current / (*period * 0.5)) % 2.0;
-
-                let t = ((if t < 1.0 { t } else { 2.0 - t } - 0.5) * 1.2 + 0.5).clamp(0.0, 1.0);
-                let t = t * t * (3.0 - 2.0 * t);
-                let p = elevator.start * (1.0 - t) + elevator.end * t;
-                transform.translation.x = p.x;
-                transform.translation.y = p.y;
-            }
+        let step = target - transform.translation.truncate();
+        if config.physics_pipeline_active && crushes(&context, &bodies, entity, step) {
+            // Wait until whatever is in the way is gone
+            velocity.linvel = Vec2::ZERO;
+            continue;
         }
+
+        drive(&mut transform, &mut velocity, target, FIXED_DT, &config);
+        elevator.elevator_type = moved;
     }
 }
@@ -0,0 +1,205 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::core::{
+    Combobox, ComboboxState, ComboboxType, GravityChanged, Player, SimulationApp, GRAVITY_FORCE,
+};
+use crate::levels::Rewind;
+use crate::states::LevelState;
+use crate::utils::SceneDirection;
+
+/// Runs after every body got the gravity it falls with this frame.
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GravitySystem;
+
+/// A sensor area where players and boxes fall with `gravity` instead of the gravity of the level.
+#[derive(Component, Debug, Clone)]
+pub struct GravityZone {
+    /// In pixels per second squared, like [`RapierConfiguration::gravity`].
+    pub gravity: Vec2,
+}
+
+/// Makes a player fall towards `direction` wherever they are, while the other player keeps
+/// falling with the level. Put on by touching a [`ComboboxType::Boots`] box.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct GravityBoots {
+    pub direction: Vec2,
+}
+
+/// The gravity a player or a box falls with, from its own direction, the zone it is in or the
+/// level. Rapier's gravity is turned off for them and applied as a force instead.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct BodyGravity(pub Vec2);
+
+impl Default for BodyGravity {
+    fn default() -> Self {
+        BodyGravity(Vec2::NEG_Y * GRAVITY_FORCE)
+    }
+}
+
+impl BodyGravity {
+    pub fn direction(&self) -> SceneDirection {
+        SceneDirection::from_gravity_direction(self.0)
+    }
+}
+
+pub struct GravityPlugin;
+
+impl Plugin for GravityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_simulation_system_set(
+            SystemSet::on_update(LevelState::Level)
+                .with_system(change_gravity)
+                .with_system(put_on_boots.before(GravitySystem))
+                .with_system(apply_gravity.after(change_gravity).label(GravitySystem)),
+        );
+    }
+}
+
+/// Gravity at `position`, from the zones there or the level. Overlapping zones add up.
+pub fn gravity_at(
+    position: Vec2,
+    context: &RapierContext,
+    zones: &Query<&GravityZone>,
+    config: &RapierConfiguration,
+) -> Vec2 {
+    let mut gravity = None;
+    let is_zone = |entity| zones.contains(entity);
+    context.intersections_with_point(
+        position,
+        QueryFilter::new().exclude_solids().predicate(&is_zone),
+        |entity| {
+            if let Ok(zone) = zones.get(entity) {
+                *gravity.get_or_insert(Vec2::ZERO) += zone.gravity;
+            }
+            true
+        },
+    );
+    gravity.unwrap_or(config.gravity)
+}
+
+/// Gravity boxes turn the gravity of the whole level.
+fn change_gravity(
+    comboboxes: Query<(&Combobox, &ComboboxState)>,
+    rigidbodies: Query<&RapierRigidBodyHandle>,
+    mut config: ResMut<RapierConfiguration>,
+    mut context: ResMut<RapierContext>,
+    mut prev_gravity: Local<Vec2>,
+    mut events: EventWriter<GravityChanged>,
+) {
+    config.gravity = Vec2::NEG_Y * GRAVITY_FORCE;
+
+    for (combobox, state) in comboboxes.iter() {
+        if combobox.box_type == ComboboxType::Gravity && *state == ComboboxState::Normal {
+            if let Some(gravity) = combobox.local_gravity {
+                config.gravity = gravity * GRAVITY_FORCE;
+            }
+        }
+    }
+
+    if (*prev_gravity - config.gravity).length() > 0.1 {
+        for handle in rigidbodies.iter() {
+            if let Some(rb) = context.bodies.get_mut(handle.0) {
+                rb.wake_up(true);
+            }
+        }
+
+        // Gravity isn't known before the first frame of the level
+        if *prev_gravity != Vec2::ZERO {
+            events.send(GravityChanged {
+                from: *prev_gravity,
+                to: config.gravity,
+            });
+        }
+    }
+
+    *prev_gravity = config.gravity;
+}
+
+/// Players touching a boots box wear its gravity from then on, boots without one take the
+/// player's boots off.
+fn put_on_boots(
+    mut commands: Commands,
+    players: Query<Entity, With<Player>>,
+    boxes: Query<(Entity, &Combobox, &ComboboxState)>,
+    context: Res<RapierContext>,
+    rewind: Res<Rewind>,
+) {
+    if rewind.is_rewinding() {
+        return;
+    }
+
+    for (entity, combobox, state) in boxes.iter() {
+        if combobox.box_type != ComboboxType::Boots || *state != ComboboxState::Normal {
+            continue;
+        }
+
+        let touching = players.iter().find(|player| {
+            context
+                .contact_pair(*player, entity)
+                .is_some_and(|contact| contact.has_any_active_contacts())
+        });
+        let player = match touching {
+            Some(player) => player,
+            None => continue,
+        };
+
+        match combobox.local_gravity {
+            Some(direction) => {
+                commands.entity(player).insert(GravityBoots { direction });
+            }
+            None => {
+                commands.entity(player).remove::<GravityBoots>();
+            }
+        }
+        commands
+            .entity(entity)
+            .insert(RigidBody::KinematicPositionBased)
+            .insert(ComboboxState::DespawningAnimation(0.0))
+            .insert(CollisionGroups::new(0, 0));
+    }
+}
+
+type GravityBodyQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static GlobalTransform,
+        &'static mut BodyGravity,
+        Option<&'static Combobox>,
+        Option<&'static GravityBoots>,
+        &'static RapierRigidBodyHandle,
+    ),
+>;
+
+fn apply_gravity(
+    mut bodies: GravityBodyQuery,
+    zones: Query<&GravityZone>,
+    config: Res<RapierConfiguration>,
+    mut context: ResMut<RapierContext>,
+) {
+    for (transform, mut body_gravity, combobox, boots, handle) in bodies.iter_mut() {
+        // Direction boxes and boots keep their own gravity everywhere
+        let own_gravity = combobox
+            .and_then(|combobox| combobox.local_gravity)
+            .or_else(|| boots.map(|boots| boots.direction));
+        let gravity = match own_gravity {
+            Some(direction) => direction * GRAVITY_FORCE,
+            None => gravity_at(
+                transform.translation().truncate(),
+                &context,
+                &zones,
+                &config,
+            ),
+        };
+        let changed = gravity != body_gravity.0;
+        body_gravity.0 = gravity;
+
+        if let Some(rb) = context.bodies.get_mut(handle.0) {
+            let force = gravity * rb.mass();
+            rb.set_gravity_scale(0.0, changed);
+            rb.reset_forces(false);
+            rb.add_force(force.into(), changed);
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::core::collision_groups;
+
+/// Runs after doors and elevators moved for the step.
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KinematicSystem;
+
+/// Stacks of boxes and players taller than this are assumed to give way.
+const MAX_STACK: usize = 4;
+
+/// Shapes are cast this much smaller, so that resting on something doesn't count as being
+/// pushed into it.
+const SKIN: f32 = 1.0;
+
+pub type KinematicBodyQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static GlobalTransform,
+        &'static Collider,
+        &'static RigidBody,
+        Option<&'static CollisionGroups>,
+    ),
+>;
+
+/// Gives a kinematic body the velocity that takes it to `target` within this frame, so Rapier
+/// carries whatever rides on it. Physics is paused while rewinding, then it is put there directly.
+pub fn drive(
+    transform: &mut Transform,
+    velocity: &mut Velocity,
+    target: Vec2,
+    delta: f32,
+    config: &RapierConfiguration,
+) {
+    if config.physics_pipeline_active && delta > 0.0 {
+        velocity.linvel = (target - transform.translation.truncate()) / delta;
+    } else {
+        velocity.linvel = Vec2::ZERO;
+        transform.translation.x = target.x;
+        transform.translation.y = target.y;
+    }
+}
+
+/// Whether moving the kinematic `body` by `step` would crush a player or a box against
+/// something that doesn't give way. Whatever is in the volume the body sweeps gets cast along
+/// the same step, and so does whatever that runs into.
+pub fn crushes(
+    context: &RapierContext,
+    bodies: &KinematicBodyQuery,
+    body: Entity,
+    step: Vec2,
+) -> bool {
+    let (transform, collider, ..) = match bodies.get(body) {
+        Ok(body) => body,
+        Err(_) => return false,
+    };
+    let half_size = match collider.as_cuboid() {
+        Some(cuboid) => cuboid.half_extents(),
+        None => return false,
+    };
+    if step == Vec2::ZERO {
+        return false;
+    }
+
+    let position = transform.translation().truncate();
+    let direction = step.normalize();
+    let reach = (half_size * direction).abs().dot(Vec2::ONE);
+
+    let mut in_the_way = vec![];
+    context.intersections_with_shape(
+        position + step * 0.5,
+        0.0,
+        &Collider::cuboid(
+            half_size.x + step.x.abs() * 0.5,
+            half_size.y + step.y.abs() * 0.5,
+        ),
+        QueryFilter::new()
+            .groups(collision_groups::ELEVATOR_I)
+            .exclude_collider(body),
+        |entity| {
+            // Riders and whatever is beside the body aren't in its way
+            let ahead = bodies.get(entity).is_ok_and(|(other, ..)| {
+                (other.translation().truncate() - position).dot(direction) > reach
+            });
+            if ahead {
+                in_the_way.push(entity);
+            }
+            true
+        },
+    );
+
+    let mut visited = vec![body];
+    in_the_way
+        .into_iter()
+        .any(|entity| pinned(context, bodies, entity, step, &mut visited, 0))
+}
+
+fn pinned(
+    context: &RapierContext,
+    bodies: &KinematicBodyQuery,
+    entity: Entity,
+    step: Vec2,
+    visited: &mut Vec<Entity>,
+    depth: usize,
+) -> bool {
+    let (transform, collider, rigid_body, groups) = match bodies.get(entity) {
+        Ok(body) => body,
+        Err(_) => return false,
+    };
+    if *rigid_body != RigidBody::Dynamic {
+        return true;
+    }
+    if depth >= MAX_STACK || visited.contains(&entity) {
+        return false;
+    }
+    visited.push(entity);
+
+    let half_size = match collider.as_cuboid() {
+        Some(cuboid) => cuboid.half_extents() - SKIN,
+        None => return false,
+    };
+    let groups = groups.map_or(InteractionGroups::all(), |groups| {
+        InteractionGroups::new(groups.memberships, groups.filters)
+    });
+    let skip_visited = |other| !visited.contains(&other);
+
+    let hit = context.cast_shape(
+        transform.translation().truncate(),
+        0.0,
+        step + step.normalize() * SKIN * 2.0,
+        &Collider::cuboid(half_size.x.max(SKIN), half_size.y.max(SKIN)),
+        1.0,
+        QueryFilter::new().groups(groups).predicate(&skip_visited),
+    );
+
+    match hit {
+        Some((other, _)) => pinned(context, bodies, other, step, visited, depth + 1),
+        None => false,
+    }
+}
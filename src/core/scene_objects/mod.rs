@@ -2,19 +2,29 @@ use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
 pub use combobox::*;
+pub use dispenser::*;
 pub use door::*;
 pub use elevator::*;
+pub use gravity::*;
+pub use kinematic::*;
 pub use player::*;
+pub use recipes::*;
+pub use signal::*;
 
-use crate::core::{FinishPoint, FinishPointArrow, Hint, Material};
+use crate::core::{FinishPoint, FinishPointArrow, Hint, Material, SimulationApp, SimulationStep};
 use crate::states::LevelState;
 use crate::utils::SceneDirection;
 
 pub mod collision_groups;
 mod combobox;
+mod dispenser;
 mod door;
 mod elevator;
+mod gravity;
+mod kinematic;
 mod player;
+mod recipes;
+mod signal;
 
 pub const GRAVITY_FORCE: f32 = 9.8 * 100.;
 
@@ -26,8 +36,14 @@ impl Plugin for SceneObjectsPlugin {
         app.add_plugin(ComboboxPlugin);
         app.add_plugin(ElevatorPlugin);
         app.add_plugin(DoorPlugin);
+        app.add_plugin(SignalPlugin);
+        app.add_plugin(DispenserPlugin);
+        app.add_plugin(GravityPlugin);
 
-        app.add_system_to_stage(CoreStage::PreUpdate, clean_impulse);
+        app.add_simulation_system_set_to_stage(
+            SimulationStep::First,
+            SystemSet::new().with_system(clean_impulse),
+        );
         app.add_system_set(SystemSet::on_update(LevelState::Level).with_system(move_finish_arrow));
         app.add_system(show_hints);
     }
@@ -39,15 +55,25 @@ fn clean_impulse(mut impulses: Query<&mut ExternalImpulse>) {
     }
 }
 
+type FinishQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static mut Transform, &'static GlobalTransform),
+    (With<FinishPoint>, Without<FinishPointArrow>),
+>;
+
 fn move_finish_arrow(
     mut query: Query<&mut Transform, (With<FinishPointArrow>, Without<FinishPoint>)>,
-    mut finish: Query<&mut Transform, (With<FinishPoint>, Without<FinishPointArrow>)>,
+    mut finish: FinishQuery,
+    zones: Query<&GravityZone>,
     time: Res<Time>,
+    context: Res<RapierContext>,
     config: Res<RapierConfiguration>,
 ) {
-    let gravity_direction = SceneDirection::from_gravity_direction(&config);
-
-    for mut finish in finish.iter_mut() {
+    for (mut finish, global) in finish.iter_mut() {
+        let position = global.translation().truncate();
+        let gravity = gravity_at(position, &context, &zones, &config);
+        let gravity_direction = SceneDirection::from_gravity_direction(gravity);
         finish.rotation = Quat::from_rotation_arc_2d(Vec2::NEG_Y, gravity_direction.get_vec());
     }
 
@@ -63,9 +89,15 @@ fn show_hints(
     mut query: Query<(&GlobalTransform, &Handle<Material>), With<Hint>>,
     players: Query<&GlobalTransform, With<Player>>,
     mut materials: ResMut<Assets<Material>>,
+    level_state: Res<State<LevelState>>,
 ) {
     for (t, m) in query.iter() {
-        let mut opacity: f32 = 0.0;
+        // There are no players in the editor, hints should still be visible
+        let mut opacity: f32 = if *level_state.current() == LevelState::Editor {
+            1.0
+        } else {
+            0.0
+        };
         for pt in players.iter() {
             let l: f32 = (t.translation() - pt.translation()).truncate().length();
 
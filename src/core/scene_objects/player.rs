@@ -1,8 +1,13 @@
-use crate::core::{collision_groups, Combobox, PlayerRectState, GRAVITY_FORCE};
+use crate::core::{
+    collision_groups, BodyGravity, Combobox, GravitySystem, PlayerGrabbed, PlayerJumped,
+    PlayerRectState, ScreenDown, ScreenDownSystem, SimulationApp, FIXED_DT, GRAVITY_FORCE,
+};
+use crate::levels::Rewind;
 use crate::states::LevelState;
 use crate::utils::SceneDirection;
 use bevy::{math::Vec3Swizzles, prelude::*};
 use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
 
 pub struct PlayerPlugin;
 
@@ -11,12 +16,13 @@ impl Plugin for PlayerPlugin {
         app.insert_resource(PlayersSettings {
             player_type: [PlayerType::Color(1), PlayerType::None],
         });
-        app.add_system_set(
+        app.add_simulation_system_set(
             SystemSet::on_update(LevelState::Level)
-                .with_system(move_player)
-                .with_system(jump_player)
-                .with_system(update_rect_state)
-                .with_system(grab),
+                .with_system(move_player.after(ScreenDownSystem))
+                // Jumping replaces the impulse that moving adds to
+                .with_system(jump_player.after(ScreenDownSystem).before(move_player))
+                .with_system(update_rect_state.after(ScreenDownSystem))
+                .with_system(grab.after(GravitySystem)),
         );
     }
 }
@@ -45,7 +51,7 @@ impl MoveKeyGroups {
 
 pub const MAX_PLAYERS_NUM: usize = 2;
 
-#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
 pub enum PlayerType {
     #[default]
     None,
@@ -129,7 +135,7 @@ pub struct Player {
     pub index: PlayerIndex,
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
 pub enum PlayerIndex {
     #[default]
     SinglePlayer,
@@ -263,18 +269,23 @@ impl Player {
 
 fn grab(
     mut commands: Commands,
-    mut players: Query<(Entity, &GlobalTransform, &mut Player, Option<&ImpulseJoint>)>,
+    mut players: Query<(
+        Entity,
+        &GlobalTransform,
+        &mut Player,
+        &BodyGravity,
+        Option<&ImpulseJoint>,
+    )>,
     boxes: Query<(&GlobalTransform, &Combobox), With<Combobox>>,
     context: Res<RapierContext>,
     keys: Res<Input<KeyCode>>,
-    config: ResMut<RapierConfiguration>,
-    time: Res<Time>,
+    mut events: EventWriter<PlayerGrabbed>,
 ) {
-    let gravity_direction = SceneDirection::from_gravity_direction(&config);
+    for (entity, transform, mut player, gravity, maybe_joint) in players.iter_mut() {
+        let gravity_direction = gravity.direction();
 
-    for (entity, transform, mut player, maybe_joint) in players.iter_mut() {
         if maybe_joint.is_none() {
-            player.ungrab_time += time.delta_seconds();
+            player.ungrab_time += FIXED_DT;
         } else {
             player.ungrab_time = 0.0;
         }
@@ -309,6 +320,10 @@ fn grab(
 
                             let joint = FixedJointBuilder::new().local_anchor2(offset);
                             commands.entity(entity).insert(ImpulseJoint::new(e, joint));
+                            events.send(PlayerGrabbed {
+                                player: entity,
+                                combobox: e,
+                            });
                             break;
                         }
                     }
@@ -318,7 +333,8 @@ fn grab(
             }
         }
 
-        if keys.any_just_released(player.get_buttons_grab()) {
+        // Not `just_released`, a step may not run in the frame the key is released
+        if maybe_joint.is_some() && !keys.any_pressed(player.get_buttons_grab()) {
             commands.entity(entity).remove::<ImpulseJoint>();
         }
 
@@ -333,23 +349,28 @@ fn grab(
     }
 }
 
+type MovingPlayerQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut ExternalImpulse,
+        &'static Velocity,
+        &'static ReadMassProperties,
+        &'static mut Player,
+        &'static BodyGravity,
+        Option<&'static mut ImpulseJoint>,
+    ),
+>;
+
 fn move_player(
-    mut players: Query<(
-        &mut ExternalImpulse,
-        &Velocity,
-        &ReadMassProperties,
-        &mut Player,
-        Option<&mut ImpulseJoint>,
-    )>,
+    mut players: MovingPlayerQuery,
     mut boxes: Query<(&mut Transform, &Combobox)>,
     keys: Res<Input<KeyCode>>,
-    time: Res<Time>,
+    screen_down: Res<ScreenDown>,
     context: Res<RapierContext>,
-    config: ResMut<RapierConfiguration>,
 ) {
-    let gravity_direction = SceneDirection::from_gravity_direction(&*config);
-
-    for (mut impulse, velocity, mass, mut player, mut maybe_joint) in players.iter_mut() {
+    for (mut impulse, velocity, mass, mut player, gravity, mut maybe_joint) in players.iter_mut() {
+        let gravity_direction = gravity.direction();
         let mut target_velocity = 0.0;
 
         let mut moving = false;
@@ -357,13 +378,14 @@ fn move_player(
         let right = gravity_direction.get_perp().get_vec();
         let mut dir = Vec2::ZERO;
 
-        if keys.any_pressed(player.get_buttons_left(gravity_direction)) {
+        let keys_direction = screen_down.on_screen(gravity_direction);
+        if keys.any_pressed(player.get_buttons_left(keys_direction)) {
             target_velocity -= player.max_speed;
             moving = true;
             dir = -right;
         }
 
-        if keys.any_pressed(player.get_buttons_right(gravity_direction)) {
+        if keys.any_pressed(player.get_buttons_right(keys_direction)) {
             target_velocity += player.max_speed;
             moving = true;
             dir = right;
@@ -395,7 +417,7 @@ fn move_player(
                                 Vec2::Y,
                                 0.5,
                                 true,
-                                QueryFilter::new(),
+                                QueryFilter::new().exclude_sensors(),
                             )
                             .is_some()
                         {
@@ -428,7 +450,7 @@ fn move_player(
 
         let dv = delta_velocity
             .abs()
-            .min(max_acceleration * time.delta_seconds() * (1.0 + k));
+            .min(max_acceleration * FIXED_DT * (1.0 + k));
 
         impulse.impulse += right * delta_velocity.signum() * dv * mass.0.mass;
     }
@@ -442,15 +464,22 @@ fn jump_player(
         &Velocity,
         &GlobalTransform,
         &ReadMassProperties,
+        &BodyGravity,
     )>,
     context: Res<RapierContext>,
     keys: Res<Input<KeyCode>>,
-    config: Res<RapierConfiguration>,
+    screen_down: Res<ScreenDown>,
+    rewind: Res<Rewind>,
+    mut events: EventWriter<PlayerJumped>,
 ) {
-    let gravity_direction = SceneDirection::from_gravity_direction(&config);
+    if rewind.is_rewinding() {
+        return;
+    }
 
-    for (entity, mut ext_impulse, player, velocity, transform, mass) in players.iter_mut() {
-        if keys.any_pressed(player.get_buttons_jump(gravity_direction)) {
+    for (entity, mut ext_impulse, player, velocity, transform, mass, gravity) in players.iter_mut()
+    {
+        let gravity_direction = gravity.direction();
+        if keys.any_pressed(player.get_buttons_jump(screen_down.on_screen(gravity_direction))) {
             let collider_below = player.find_obstacle(
                 entity,
                 gravity_direction,
@@ -467,28 +496,40 @@ fn jump_player(
                 let delta = jump_velocity + proj;
 
                 if dist < 0.1 {
-                    ext_impulse.impulse = -config.gravity.normalize() * delta * mass.0.mass;
+                    ext_impulse.impulse = -gravity_direction.get_vec() * delta * mass.0.mass;
+
+                    // Holding the key keeps pushing while the player is still taking off
+                    if delta > jump_velocity * 0.5 {
+                        events.send(PlayerJumped { player: entity });
+                    }
                 }
             }
         }
     }
 }
 
+type RectStateQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut PlayerRectState,
+        &'static Player,
+        &'static GlobalTransform,
+        &'static BodyGravity,
+        Option<&'static ImpulseJoint>,
+    ),
+>;
+
 fn update_rect_state(
     mut commands: Commands,
-    mut query: Query<(
-        Entity,
-        &mut PlayerRectState,
-        &Player,
-        &GlobalTransform,
-        Option<&ImpulseJoint>,
-    )>,
+    mut query: RectStateQuery,
     keys: Res<Input<KeyCode>>,
-    config: Res<RapierConfiguration>,
+    screen_down: Res<ScreenDown>,
     context: Res<RapierContext>,
 ) {
-    let gravity_direction = SceneDirection::from_gravity_direction(&config);
-    for (entity, mut rect_state, player, transform, maybe_joint) in query.iter_mut() {
+    for (entity, mut rect_state, player, transform, gravity, maybe_joint) in query.iter_mut() {
+        let gravity_direction = gravity.direction();
         let prev_rotation = rect_state.current_rotation;
         let prev_state = rect_state.current_state;
 
@@ -496,9 +537,10 @@ fn update_rect_state(
 
         let _legs_origin = transform.translation().xy() + gravity_direction.get_vec() * 10.0;
 
-        if keys.any_pressed(player.get_buttons_right(gravity_direction)) {
+        let keys_direction = screen_down.on_screen(gravity_direction);
+        if keys.any_pressed(player.get_buttons_right(keys_direction)) {
             rect_state.current_state = 1;
-        } else if keys.any_pressed(player.get_buttons_left(gravity_direction)) {
+        } else if keys.any_pressed(player.get_buttons_left(keys_direction)) {
             rect_state.current_state = 0;
         }
 
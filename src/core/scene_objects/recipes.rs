@@ -0,0 +1,257 @@
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use serde::{Deserialize, Serialize};
+
+use crate::core::{Combobox, ComboboxType};
+
+/// Recipes every level uses, relative to the assets folder.
+pub const DEFAULT_RECIPES_PATH: &str = "merge.recipes.ron";
+
+/// Which boxes combine and what they turn into. Earlier recipes win, so levels and level
+/// packs put their own in front of the default ones, see [`MergeRecipes::with`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TypeUuid)]
+#[uuid = "9d2e4b71-5c3a-4f8e-a1b6-7e0c3d9f2a58"]
+pub struct MergeRecipes {
+    pub recipes: Vec<MergeRecipe>,
+}
+
+/// Combines a `first` and a `second` box, touching in either order, into one box.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeRecipe {
+    pub first: BoxKind,
+    pub second: BoxKind,
+    /// Only boxes of the same group or color combine.
+    #[serde(default)]
+    pub same_variant: bool,
+    pub weight: WeightRule,
+    pub box_type: TypeRule,
+    pub gravity: GravityRule,
+    pub position: PositionRule,
+    #[serde(default)]
+    pub pieces: PiecesRule,
+    /// An undo box splits the result back into the two boxes.
+    #[serde(default = "undoable_default")]
+    pub undoable: bool,
+    /// Where undoing puts the two boxes.
+    #[serde(default)]
+    pub undo_offsets: OffsetsRule,
+}
+
+fn undoable_default() -> bool {
+    true
+}
+
+/// [`ComboboxType`] without its parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BoxKind {
+    Standard,
+    Buff,
+    Undo,
+    Direction,
+    Gravity,
+    Lamp,
+    Split,
+    Boots,
+}
+
+impl BoxKind {
+    pub fn of(box_type: &ComboboxType) -> BoxKind {
+        match box_type {
+            ComboboxType::Standard { .. } => BoxKind::Standard,
+            ComboboxType::Buff(_) => BoxKind::Buff,
+            ComboboxType::Undo => BoxKind::Undo,
+            ComboboxType::Direction { .. } => BoxKind::Direction,
+            ComboboxType::Gravity => BoxKind::Gravity,
+            ComboboxType::Lamp { .. } => BoxKind::Lamp,
+            ComboboxType::Split(_) => BoxKind::Split,
+            ComboboxType::Boots => BoxKind::Boots,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WeightRule {
+    Sum,
+    First,
+    Second,
+    /// The second box's weight times the first box's buff.
+    Scaled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TypeRule {
+    First,
+    Second,
+    /// A lamp with both colors mixed by weight.
+    MixColors,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GravityRule {
+    /// Kept only when both boxes fall the same way.
+    Common,
+    First,
+    Second,
+    /// Falls the way the direction box points.
+    Direction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PositionRule {
+    /// Between the boxes, closer to the bigger one.
+    Center,
+    First,
+    Second,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PiecesRule {
+    #[default]
+    One,
+    /// As many equal pieces as the split box says, side by side around the position.
+    Split,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OffsetsRule {
+    /// Each box where it was, seen from the merged box.
+    #[default]
+    Piece,
+    /// The first box at the merged box, and the second where it was seen from the first, like
+    /// buffed boxes always came apart.
+    First,
+}
+
+impl MergeRecipes {
+    /// The default recipes the game was built with, for when the file can't be loaded.
+    pub fn builtin() -> MergeRecipes {
+        ron::de::from_str(include_str!("../../../assets/merge.recipes.ron"))
+            .expect("the built-in merge recipes are valid")
+    }
+
+    /// `overrides` take precedence over these recipes.
+    pub fn with(&self, overrides: &[MergeRecipe]) -> MergeRecipes {
+        MergeRecipes {
+            recipes: overrides.iter().chain(&self.recipes).cloned().collect(),
+        }
+    }
+
+    /// The recipe for the two boxes, and whether they have to be swapped to match it.
+    pub fn find(&self, a: &Combobox, b: &Combobox) -> Option<(&MergeRecipe, bool)> {
+        self.recipes.iter().find_map(|recipe| {
+            if recipe.matches(a, b) {
+                Some((recipe, false))
+            } else if recipe.matches(b, a) {
+                Some((recipe, true))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl MergeRecipe {
+    fn matches(&self, first: &Combobox, second: &Combobox) -> bool {
+        BoxKind::of(&first.box_type) == self.first
+            && BoxKind::of(&second.box_type) == self.second
+            && (!self.same_variant || first.box_type == second.box_type)
+    }
+
+    pub fn apply(
+        &self,
+        first: &Combobox,
+        first_pos: Vec2,
+        second: &Combobox,
+        second_pos: Vec2,
+    ) -> Vec<(Combobox, Vec2)> {
+        let position = match self.position {
+            PositionRule::Center => {
+                (first_pos * first.world_size() + second_pos * second.world_size())
+                    / (first.world_size() + second.world_size())
+            }
+            PositionRule::First => first_pos,
+            PositionRule::Second => second_pos,
+        };
+
+        let weight = match self.weight {
+            WeightRule::Sum => first.weight + second.weight,
+            WeightRule::First => first.weight,
+            WeightRule::Second => second.weight,
+            WeightRule::Scaled => match first.box_type {
+                ComboboxType::Buff(buff) => second.weight * buff,
+                _ => second.weight,
+            },
+        };
+
+        let box_type = match (self.box_type, &first.box_type, &second.box_type) {
+            (TypeRule::First, ..) => first.box_type.clone(),
+            (TypeRule::Second, ..) => second.box_type.clone(),
+            (
+                TypeRule::MixColors,
+                ComboboxType::Lamp { color: color1 },
+                ComboboxType::Lamp { color: color2 },
+            ) => {
+                let t = second.weight / (first.weight + second.weight);
+                let mixed = Vec4::from(color1.as_rgba_f32()).lerp(color2.as_rgba_f32().into(), t);
+                ComboboxType::Lamp {
+                    color: Color::from(mixed),
+                }
+            }
+            (TypeRule::MixColors, ..) => first.box_type.clone(),
+        };
+
+        let direction = |combobox: &Combobox| match combobox.box_type {
+            ComboboxType::Direction { direction } => Some(direction),
+            _ => None,
+        };
+
+        let local_gravity = match self.gravity {
+            GravityRule::Common if first.local_gravity == second.local_gravity => {
+                first.local_gravity
+            }
+            GravityRule::Common => None,
+            GravityRule::First => first.local_gravity,
+            GravityRule::Second => second.local_gravity,
+            GravityRule::Direction => direction(first).or_else(|| direction(second)),
+        };
+
+        let pieces = match (self.pieces, &first.box_type, &second.box_type) {
+            (PiecesRule::Split, ComboboxType::Split(pieces), _)
+            | (PiecesRule::Split, _, ComboboxType::Split(pieces)) => (*pieces).max(1),
+            _ => 1,
+        };
+
+        let piece = Combobox {
+            weight: weight / pieces as f32,
+            box_type,
+            combined_from: vec![],
+            local_gravity,
+        };
+        // Far enough apart for the pieces not to merge right away, like after an undo
+        let spacing = piece.world_size() * 1.1 + 10.0;
+        // Side by side on whatever they fall onto, boxes without their own gravity fall down
+        let across = piece
+            .local_gravity
+            .map_or(Vec2::X, |gravity| gravity.perp().normalize_or_zero());
+
+        (0..pieces)
+            .map(|i| {
+                let offset = (i as f32 - (pieces - 1) as f32 * 0.5) * spacing;
+                let piece_pos = position + across * offset;
+
+                let mut piece = piece.clone();
+                if self.undoable {
+                    let origin = match self.undo_offsets {
+                        OffsetsRule::Piece => piece_pos,
+                        OffsetsRule::First => first_pos,
+                    };
+                    piece.combined_from = vec![
+                        (first.clone(), first_pos - origin),
+                        (second.clone(), second_pos - origin),
+                    ];
+                }
+                (piece, piece_pos)
+            })
+            .collect()
+    }
+}
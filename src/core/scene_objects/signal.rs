@@ -0,0 +1,300 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use post_processing::PointLight2d;
+use serde::{Deserialize, Serialize};
+
+use crate::core::{Combobox, ComboboxState, DoorButton, Player, SimulationApp, FIXED_DT};
+use crate::levels::Rewind;
+use crate::states::LevelState;
+
+/// Runs after everything that turns signals on, and before everything that reacts to them.
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SignalSystem;
+
+/// Turns `output` on depending on other signals. Levels list them in
+/// [`crate::levels::LevelDescription::logic`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Gate {
+    pub output: String,
+    pub kind: GateKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum GateKind {
+    And(Vec<String>),
+    Or(Vec<String>),
+    /// On while an odd number of the inputs are.
+    Xor(Vec<String>),
+    Not(String),
+    /// Turns on with `set` and stays on until `reset`.
+    Latch {
+        set: String,
+        reset: String,
+    },
+    /// Flips every time the input turns on.
+    Toggle(String),
+    /// Follows the input `seconds` later.
+    Delay {
+        input: String,
+        seconds: f32,
+    },
+    /// On for `seconds` after the input turns on.
+    Timer {
+        input: String,
+        seconds: f32,
+    },
+    /// On once the input turned on `count` times, until `reset` starts over.
+    Counter {
+        input: String,
+        count: u32,
+        #[serde(default)]
+        reset: Option<String>,
+    },
+}
+
+impl GateKind {
+    pub fn inputs(&self) -> Vec<&str> {
+        match self {
+            GateKind::And(inputs) | GateKind::Or(inputs) | GateKind::Xor(inputs) => {
+                inputs.iter().map(String::as_str).collect()
+            }
+            GateKind::Not(input)
+            | GateKind::Toggle(input)
+            | GateKind::Delay { input, .. }
+            | GateKind::Timer { input, .. } => vec![input],
+            GateKind::Latch { set, reset } => vec![set, reset],
+            GateKind::Counter { input, reset, .. } => [Some(input), reset.as_ref()]
+                .into_iter()
+                .flatten()
+                .map(String::as_str)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct GateState {
+    on: bool,
+    /// The input in the previous frame, to see when it turns on.
+    input: bool,
+    timer: f32,
+    count: u32,
+    /// Input changes on their way through a delay, with the time they come out.
+    delayed: VecDeque<(f32, bool)>,
+}
+
+impl GateState {
+    fn update(
+        &mut self,
+        kind: &GateKind,
+        read: impl Fn(&str) -> bool,
+        time: f32,
+        delta: f32,
+    ) -> bool {
+        match kind {
+            GateKind::And(inputs) => inputs.iter().all(|input| read(input)),
+            GateKind::Or(inputs) => inputs.iter().any(|input| read(input)),
+            GateKind::Xor(inputs) => inputs.iter().filter(|input| read(input)).count() % 2 == 1,
+            GateKind::Not(input) => !read(input),
+            GateKind::Latch { set, reset } => {
+                if read(reset) {
+                    self.on = false;
+                } else if read(set) {
+                    self.on = true;
+                }
+                self.on
+            }
+            GateKind::Toggle(input) => {
+                if self.turned_on(read(input)) {
+                    self.on = !self.on;
+                }
+                self.on
+            }
+            GateKind::Delay { input, seconds } => {
+                let input = read(input);
+                if input != self.input {
+                    self.input = input;
+                    self.delayed.push_back((time + seconds, input));
+                }
+                while let Some((at, on)) = self.delayed.front().copied() {
+                    if at > time {
+                        break;
+                    }
+                    self.on = on;
+                    self.delayed.pop_front();
+                }
+                self.on
+            }
+            GateKind::Timer { input, seconds } => {
+                if self.turned_on(read(input)) {
+                    self.timer = *seconds;
+                }
+                let on = self.timer > 0.0;
+                self.timer -= delta;
+                on
+            }
+            GateKind::Counter {
+                input,
+                count,
+                reset,
+            } => {
+                if self.turned_on(read(input)) {
+                    self.count += 1;
+                }
+                if reset.as_deref().is_some_and(&read) {
+                    self.count = 0;
+                }
+                self.count >= *count
+            }
+        }
+    }
+
+    fn turned_on(&mut self, input: bool) -> bool {
+        let turned_on = input && !self.input;
+        self.input = input;
+        turned_on
+    }
+}
+
+/// The signals of the level being played and the gates between them, set up by `build_level`.
+#[derive(Debug, Clone, Default)]
+pub struct Circuit {
+    gates: Vec<(Gate, GateState)>,
+    signals: HashMap<String, bool>,
+    previous: HashMap<String, bool>,
+    time: f32,
+}
+
+impl Circuit {
+    pub fn new(gates: &[Gate]) -> Circuit {
+        Circuit {
+            gates: gates
+                .iter()
+                .map(|gate| (gate.clone(), GateState::default()))
+                .collect(),
+            ..default()
+        }
+    }
+
+    pub fn is_on(&self, signal: &str) -> bool {
+        self.signals.get(signal).copied().unwrap_or(false)
+    }
+
+    /// Whether the signal was off last frame and is on now.
+    pub fn turned_on(&self, signal: &str) -> bool {
+        self.is_on(signal) && !self.previous.get(signal).copied().unwrap_or(false)
+    }
+
+    /// Whether something listening to `signal` is active, it always is without a signal.
+    pub fn allows(&self, signal: Option<&str>) -> bool {
+        signal.is_none_or(|signal| self.is_on(signal))
+    }
+
+    /// `sources` sharing a signal turn it on together. Gates run in the order they are listed, so
+    /// a gate sees this frame's output of the gates before it, and last frame's of the ones after.
+    pub fn update<'a>(&mut self, sources: impl IntoIterator<Item = (&'a str, bool)>, delta: f32) {
+        self.time += delta;
+
+        let mut signals: HashMap<String, bool> = HashMap::default();
+        for (signal, on) in sources {
+            *signals.entry(signal.to_string()).or_default() |= on;
+        }
+
+        for (gate, state) in self.gates.iter_mut() {
+            let read = |signal: &str| {
+                signals
+                    .get(signal)
+                    .or_else(|| self.signals.get(signal))
+                    .copied()
+                    .unwrap_or(false)
+            };
+            let on = state.update(&gate.kind, read, self.time, delta);
+            *signals.entry(gate.output.clone()).or_default() |= on;
+        }
+
+        self.previous = std::mem::replace(&mut self.signals, signals);
+    }
+}
+
+/// Turns its signal on while a player or a box is inside.
+#[derive(Component, Debug, Clone)]
+pub struct AreaSensor {
+    pub size: Vec2,
+    pub signal: String,
+    pub enabled: bool,
+}
+
+/// A [`PointLight2d`] that is only lit while its signal is on.
+#[derive(Component, Debug, Clone)]
+pub struct SignalLight {
+    pub radius: f32,
+    pub signal: Option<String>,
+}
+
+pub struct SignalPlugin;
+
+impl Plugin for SignalPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Circuit>();
+        app.add_simulation_system_set(
+            SystemSet::on_update(LevelState::Level)
+                .with_system(update.label(SignalSystem))
+                .with_system(update_lights.after(SignalSystem)),
+        );
+    }
+}
+
+type BodyQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static GlobalTransform, Option<&'static ComboboxState>),
+    Or<(With<Player>, With<Combobox>)>,
+>;
+
+fn update(
+    mut circuit: ResMut<Circuit>,
+    buttons: Query<&DoorButton>,
+    mut sensors: Query<(&GlobalTransform, &mut AreaSensor)>,
+    bodies: BodyQuery,
+    rewind: Res<Rewind>,
+) {
+    // Rewinding restores the circuit
+    if rewind.is_rewinding() {
+        return;
+    }
+
+    for (transform, mut sensor) in sensors.iter_mut() {
+        let center = transform.translation().truncate();
+        let half_size = sensor.size * 0.5;
+
+        sensor.enabled = bodies.iter().any(|(body, state)| {
+            state.is_none_or(|state| *state == ComboboxState::Normal)
+                && (body.translation().truncate() - center)
+                    .abs()
+                    .cmple(half_size)
+                    .all()
+        });
+    }
+
+    let sources = buttons
+        .iter()
+        .map(|button| (button.signal.as_str(), button.enabled))
+        .chain(
+            sensors
+                .iter()
+                .map(|(_, sensor)| (sensor.signal.as_str(), sensor.enabled)),
+        );
+    circuit.update(sources, FIXED_DT);
+}
+
+fn update_lights(circuit: Res<Circuit>, mut lights: Query<(&SignalLight, &mut PointLight2d)>) {
+    for (light, mut point_light) in lights.iter_mut() {
+        point_light.radius = if circuit.allows(light.signal.as_deref()) {
+            light.radius
+        } else {
+            0.0
+        };
+    }
+}
@@ -0,0 +1,115 @@
+use bevy::prelude::*;
+use bevy::time::FixedTimestep;
+use bevy_rapier2d::prelude::*;
+
+use crate::states::LevelState;
+
+/// Seconds simulated every step. Physics and gameplay advance by exactly this much, so a
+/// run with the same input plays out the same way, see [`crate::levels::Replay`].
+pub const FIXED_DT: f32 = 1.0 / 60.0;
+
+/// Runs the simulation [`FIXED_DT`] at a time, as many steps a frame as real time calls for.
+/// Slow frames run several steps to catch up, fast ones may run none. Everything else, like the
+/// camera, audio and menus, keeps running once a frame with the real [`Time`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, StageLabel)]
+pub struct SimulationStage;
+
+/// Stages of every step, before the physics ones.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, StageLabel)]
+pub enum SimulationStep {
+    /// Input for the step, before anything reacts to it.
+    First,
+    Update,
+}
+
+pub struct TimestepPlugin;
+
+impl Plugin for TimestepPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RapierConfiguration {
+            timestep_mode: TimestepMode::Fixed {
+                dt: FIXED_DT,
+                substeps: 1,
+            },
+            ..default()
+        });
+
+        add_simulation_stage(app);
+
+        // Bodies despawned outside of the simulation
+        app.add_stage_before(
+            CoreStage::Last,
+            PhysicsStages::DetectDespawn,
+            SystemStage::parallel().with_system_set(
+                RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsStages::DetectDespawn),
+            ),
+        );
+    }
+}
+
+/// Adds systems to every step of the simulation instead of every frame.
+pub trait SimulationApp {
+    fn add_simulation_system_set(&mut self, system_set: SystemSet) -> &mut Self;
+
+    fn add_simulation_system_set_to_stage(
+        &mut self,
+        stage: SimulationStep,
+        system_set: SystemSet,
+    ) -> &mut Self;
+}
+
+impl SimulationApp for App {
+    fn add_simulation_system_set(&mut self, system_set: SystemSet) -> &mut Self {
+        self.add_simulation_system_set_to_stage(SimulationStep::Update, system_set)
+    }
+
+    fn add_simulation_system_set_to_stage(
+        &mut self,
+        stage: SimulationStep,
+        system_set: SystemSet,
+    ) -> &mut Self {
+        add_simulation_stage(self);
+        self.schedule
+            .stage(SimulationStage, |schedule: &mut Schedule| {
+                schedule.add_system_set_to_stage(stage, system_set)
+            });
+        self
+    }
+}
+
+/// Plugins may add their systems before the [`TimestepPlugin`] is added.
+fn add_simulation_stage(app: &mut App) {
+    if app
+        .schedule
+        .get_stage::<Schedule>(&SimulationStage)
+        .is_some()
+    {
+        return;
+    }
+
+    // `on_update` sets loop until the stage has a driver for their state
+    let level_stage = || SystemStage::parallel().with_system_set(State::<LevelState>::get_driver());
+    let physics = RapierPhysicsPlugin::<NoUserData>::get_systems;
+    let schedule = Schedule::default()
+        .with_run_criteria(FixedTimestep::step(FIXED_DT as f64))
+        .with_stage(SimulationStep::First, level_stage())
+        .with_stage(SimulationStep::Update, level_stage())
+        .with_stage(
+            PhysicsStages::SyncBackend,
+            SystemStage::parallel().with_system_set(physics(PhysicsStages::SyncBackend)),
+        )
+        .with_stage(
+            PhysicsStages::StepSimulation,
+            SystemStage::parallel().with_system_set(physics(PhysicsStages::StepSimulation)),
+        )
+        .with_stage(
+            PhysicsStages::Writeback,
+            SystemStage::parallel().with_system_set(physics(PhysicsStages::Writeback)),
+        )
+        .with_stage(
+            PhysicsStages::DetectDespawn,
+            SystemStage::parallel().with_system_set(physics(PhysicsStages::DetectDespawn)),
+        );
+
+    app.add_stage_after(CoreStage::Update, SimulationStage, schedule);
+}
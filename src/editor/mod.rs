@@ -0,0 +1,440 @@
+use bevy::asset::{AssetServerSettings, LoadState};
+use bevy::prelude::shape::Quad;
+use bevy::prelude::*;
+use bevy::sprite::{MaterialMesh2dBundle, Mesh2dHandle};
+
+use crate::core::{Combobox, GameCamera, Material, SceneBuilder};
+use crate::levels::{CurrentLevel, LevelDescription, LevelRegistry};
+use crate::states::{AudioState, CameraState, GuiState, LevelState};
+use crate::utils::SceneDirection;
+
+pub use tools::*;
+
+mod tools;
+
+/// Lets designers edit the current level with the mouse and play-test it with `F2`.
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Editor>();
+        app.add_system_set(SystemSet::on_update(LevelState::Level).with_system(enter_editor));
+        app.add_system_set(SystemSet::on_enter(LevelState::Editor).with_system(setup));
+        app.add_system_set(
+            SystemSet::on_update(LevelState::Editor)
+                .with_system(leave_editor)
+                .with_system(choose_tool)
+                .with_system(edit_with_mouse)
+                .with_system(edit_with_keys)
+                .with_system(save)
+                .with_system(move_camera)
+                .with_system(show_boxes)
+                .with_system(highlight_selection),
+        );
+        app.add_system_set(SystemSet::on_exit(LevelState::Editor).with_system(cleanup));
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Drag {
+    last: Vec2,
+    /// Corner that stays in place while the object is being resized.
+    anchor: Option<Vec2>,
+}
+
+pub struct Editor {
+    pub tool: EditorTool,
+    pub variant: usize,
+    pub direction: SceneDirection,
+    /// Selects the signal objects are connected to, see [`channel_signal`].
+    pub channel: u32,
+    pub selected: Option<usize>,
+    pub status: String,
+    drag: Option<Drag>,
+}
+
+impl Default for Editor {
+    fn default() -> Self {
+        Editor {
+            tool: EditorTool::Select,
+            variant: 0,
+            direction: SceneDirection::Up,
+            channel: 0,
+            selected: None,
+            status: String::new(),
+            drag: None,
+        }
+    }
+}
+
+#[derive(Component)]
+struct EditorHighlight;
+
+fn enter_editor(
+    keys: Res<Input<KeyCode>>,
+    mut level_state: ResMut<State<LevelState>>,
+    mut gui_state: ResMut<State<GuiState>>,
+    mut camera_state: ResMut<State<CameraState>>,
+) {
+    if keys.just_pressed(KeyCode::F2) && *gui_state.current() == GuiState::Level {
+        level_state.set(LevelState::Editor).unwrap();
+        gui_state.set(GuiState::Editor).unwrap();
+        camera_state.set(CameraState::None).unwrap();
+    }
+}
+
+fn leave_editor(
+    keys: Res<Input<KeyCode>>,
+    mut level_state: ResMut<State<LevelState>>,
+    mut audio_state: ResMut<State<AudioState>>,
+    mut gui_state: ResMut<State<GuiState>>,
+    mut camera_state: ResMut<State<CameraState>>,
+) {
+    if keys.just_pressed(KeyCode::F2) {
+        // Play-test, edits are kept in the level asset
+        level_state.set(LevelState::Level).unwrap();
+        gui_state.set(GuiState::Level).unwrap();
+        camera_state.set(CameraState::FollowPlayers).unwrap();
+    } else if keys.just_pressed(KeyCode::Escape) {
+        level_state.set(LevelState::None).unwrap();
+        audio_state.set(AudioState::Menu).unwrap();
+        gui_state.set(GuiState::LevelSelection).unwrap();
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    mut editor: ResMut<Editor>,
+    current_level: Res<CurrentLevel>,
+    registry: Res<LevelRegistry>,
+    assets: Res<AssetServer>,
+    mut descriptions: ResMut<Assets<LevelDescription>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<Material>>,
+) {
+    editor.selected = None;
+    editor.drag = None;
+    editor.status = format!("Editing {}", current_level.id);
+
+    // A level listed in the registry whose file doesn't exist yet starts empty
+    if let Some(handle) = registry.get_handle(&current_level.id) {
+        if assets.get_load_state(&handle) == LoadState::Failed {
+            descriptions.set_untracked(&handle, LevelDescription::default());
+        }
+    }
+
+    commands
+        .spawn_bundle(MaterialMesh2dBundle {
+            mesh: Mesh2dHandle(meshes.add(Quad::new(Vec2::ONE).into())),
+            material: materials.add(Color::rgba(1.0, 1.0, 1.0, 0.2).into()),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(EditorHighlight);
+}
+
+fn cleanup(mut commands: Commands, highlights: Query<Entity, With<EditorHighlight>>) {
+    for entity in highlights.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Cursor position in cells.
+fn cursor_position(
+    windows: &Windows,
+    cameras: &Query<(&Camera, &GlobalTransform), With<GameCamera>>,
+) -> Option<Vec2> {
+    let window = windows.get_primary()?;
+    let cursor = window.cursor_position()?;
+    let (camera, transform) = cameras.iter().find(|(camera, _)| camera.is_active)?;
+
+    let ndc = cursor / Vec2::new(window.width(), window.height()) * 2.0 - Vec2::ONE;
+    let matrix = transform.compute_matrix() * camera.projection_matrix().inverse();
+    let world = matrix.project_point3(ndc.extend(-1.0)).truncate();
+
+    Some(world / SceneBuilder::CELL_SIZE)
+}
+
+fn snap(position: Vec2) -> Vec2 {
+    (position * 2.0).round() * 0.5
+}
+
+fn edit_with_mouse(
+    mut editor: ResMut<Editor>,
+    buttons: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
+    windows: Res<Windows>,
+    cameras: Query<(&Camera, &GlobalTransform), With<GameCamera>>,
+    current_level: Res<CurrentLevel>,
+    registry: Res<LevelRegistry>,
+    mut descriptions: ResMut<Assets<LevelDescription>>,
+) {
+    let cursor = match cursor_position(&windows, &cameras) {
+        Some(cursor) => cursor,
+        None => return,
+    };
+    let snapped = snap(cursor);
+
+    let handle = match registry.get_handle(&current_level.id) {
+        Some(handle) => handle,
+        None => return,
+    };
+
+    if buttons.just_pressed(MouseButton::Left) {
+        let description = match descriptions.get(&handle) {
+            Some(description) => description,
+            None => return,
+        };
+
+        if editor.tool == EditorTool::Select {
+            // Prefer the smallest object, so things placed on walls can still be picked
+            editor.selected = description
+                .objects
+                .iter()
+                .enumerate()
+                .filter(|(_, object)| {
+                    let bounds = object.bounds();
+                    cursor.cmpge(bounds.min).all() && cursor.cmple(bounds.max).all()
+                })
+                .min_by(|(_, a), (_, b)| {
+                    let a = a.bounds().max - a.bounds().min;
+                    let b = b.bounds().max - b.bounds().min;
+                    (a.x * a.y).partial_cmp(&(b.x * b.y)).unwrap()
+                })
+                .map(|(i, _)| i);
+
+            let resize = keys.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+            editor.drag = editor.selected.map(|i| Drag {
+                last: snapped,
+                anchor: if resize {
+                    description.objects[i].resize_anchor(cursor)
+                } else {
+                    None
+                },
+            });
+        } else {
+            let object = editor.new_object(snapped);
+            let anchor = object.resize_anchor(cursor);
+
+            if let Some(description) = descriptions.get_mut(&handle) {
+                description.objects.push(object);
+                editor.selected = Some(description.objects.len() - 1);
+                editor.drag = Some(Drag {
+                    last: snapped,
+                    anchor,
+                });
+            }
+        }
+    }
+
+    if buttons.just_released(MouseButton::Left) {
+        editor.drag = None;
+
+        let empty = editor.selected.and_then(|selected| {
+            let object = descriptions.get(&handle)?.objects.get(selected)?;
+            object.is_empty().then_some(selected)
+        });
+
+        if let (Some(selected), Some(description)) = (empty, descriptions.get_mut(&handle)) {
+            description.objects.remove(selected);
+            editor.selected = None;
+        }
+    }
+
+    let (selected, drag) = match (editor.selected, editor.drag) {
+        (Some(selected), Some(drag)) => (selected, drag),
+        _ => return,
+    };
+
+    if snapped == drag.last {
+        return;
+    }
+
+    if let Some(description) = descriptions.get_mut(&handle) {
+        if let Some(object) = description.objects.get_mut(selected) {
+            if let Some(anchor) = drag.anchor {
+                object.resize(anchor, snapped);
+            } else {
+                object.translate(snapped - drag.last);
+            }
+        }
+    }
+
+    editor.drag = Some(Drag {
+        last: snapped,
+        ..drag
+    });
+}
+
+fn edit_with_keys(
+    mut editor: ResMut<Editor>,
+    keys: Res<Input<KeyCode>>,
+    current_level: Res<CurrentLevel>,
+    registry: Res<LevelRegistry>,
+    mut descriptions: ResMut<Assets<LevelDescription>>,
+) {
+    let shift = keys.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+
+    if keys.just_pressed(KeyCode::LBracket) {
+        editor.channel = (editor.channel + 31) % 32;
+    }
+    if keys.just_pressed(KeyCode::RBracket) {
+        editor.channel = (editor.channel + 1) % 32;
+    }
+    // R alone turns the selected object, with shift the direction of the next placed one
+    if shift && keys.just_pressed(KeyCode::R) {
+        editor.direction = editor.direction.get_perp();
+    }
+
+    let selected = match editor.selected {
+        Some(selected) => selected,
+        None => return,
+    };
+
+    let handle = match registry.get_handle(&current_level.id) {
+        Some(handle) => handle,
+        None => return,
+    };
+
+    let edit = if keys.any_just_pressed([KeyCode::Delete, KeyCode::Back]) {
+        Some(ObjectEdit::Delete)
+    } else if keys.just_pressed(KeyCode::Equals) {
+        Some(ObjectEdit::Grow(if shift { 1.0 } else { 0.1 }))
+    } else if keys.just_pressed(KeyCode::Minus) {
+        Some(ObjectEdit::Grow(if shift { -1.0 } else { -0.1 }))
+    } else if !shift && keys.just_pressed(KeyCode::R) {
+        Some(ObjectEdit::Rotate)
+    } else if keys.just_pressed(KeyCode::P) {
+        Some(ObjectEdit::Connect(editor.channel))
+    } else if keys.just_pressed(KeyCode::N) {
+        Some(ObjectEdit::Disconnect)
+    } else {
+        None
+    };
+
+    let edit = match edit {
+        Some(edit) => edit,
+        None => return,
+    };
+
+    if let Some(description) = descriptions.get_mut(&handle) {
+        if selected >= description.objects.len() {
+            editor.selected = None;
+            return;
+        }
+
+        if let ObjectEdit::Delete = edit {
+            description.objects.remove(selected);
+            editor.selected = None;
+            editor.drag = None;
+        } else {
+            description.objects[selected].apply(edit);
+        }
+    }
+}
+
+fn save(
+    mut editor: ResMut<Editor>,
+    keys: Res<Input<KeyCode>>,
+    current_level: Res<CurrentLevel>,
+    registry: Res<LevelRegistry>,
+    descriptions: Res<Assets<LevelDescription>>,
+    settings: Res<AssetServerSettings>,
+) {
+    if !(keys.any_pressed([KeyCode::LControl, KeyCode::RControl]) && keys.just_pressed(KeyCode::S))
+    {
+        return;
+    }
+
+    let info = match registry.get(&current_level.id) {
+        Some(info) => info,
+        None => return,
+    };
+
+    let description = registry
+        .get_handle(&info.id)
+        .and_then(|handle| descriptions.get(&handle));
+
+    if let Some(description) = description {
+        editor.status = match write_level(&settings.asset_folder, &info.path, description) {
+            Ok(()) => format!("Saved {}", info.path),
+            Err(error) => format!("Failed to save {}: {}", info.path, error),
+        };
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_level(folder: &str, path: &str, description: &LevelDescription) -> Result<(), String> {
+    let data = description.to_ron().map_err(|e| e.to_string())?;
+    let path = bevy::asset::FileAssetIo::get_base_path()
+        .join(folder)
+        .join(path);
+    std::fs::write(path, data).map_err(|e| e.to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_level(_folder: &str, _path: &str, _description: &LevelDescription) -> Result<(), String> {
+    Err("saving is not supported in the browser".to_string())
+}
+
+fn move_camera(
+    mut cameras: Query<&mut Transform, With<GameCamera>>,
+    buttons: Res<Input<MouseButton>>,
+    mut motion: EventReader<bevy::input::mouse::MouseMotion>,
+    mut wheel: EventReader<bevy::input::mouse::MouseWheel>,
+) {
+    let mut delta = Vec2::ZERO;
+    for event in motion.iter() {
+        delta += event.delta;
+    }
+
+    let mut zoom = 0.0;
+    for event in wheel.iter() {
+        zoom += event.y;
+    }
+
+    for mut transform in cameras.iter_mut() {
+        if buttons.pressed(MouseButton::Right) {
+            // Mouse motion is y-down, the world is y-up
+            let scale = transform.scale.x;
+            transform.translation.x -= delta.x * scale;
+            transform.translation.y += delta.y * scale;
+        }
+
+        if zoom != 0.0 {
+            transform.scale *= 1.1_f32.powf(-zoom.signum());
+        }
+    }
+}
+
+/// Boxes only grow to full size once they are simulated, which doesn't happen in the editor.
+fn show_boxes(mut boxes: Query<&mut Transform, With<Combobox>>) {
+    for mut transform in boxes.iter_mut() {
+        transform.scale = Vec3::ONE;
+    }
+}
+
+fn highlight_selection(
+    editor: Res<Editor>,
+    current_level: Res<CurrentLevel>,
+    registry: Res<LevelRegistry>,
+    descriptions: Res<Assets<LevelDescription>>,
+    mut highlights: Query<(&mut Transform, &mut Visibility), With<EditorHighlight>>,
+) {
+    let bounds = editor.selected.and_then(|selected| {
+        let handle = registry.get_handle(&current_level.id)?;
+        let object = descriptions.get(&handle)?.objects.get(selected)?;
+        Some(object.bounds())
+    });
+
+    for (mut transform, mut visibility) in highlights.iter_mut() {
+        visibility.is_visible = bounds.is_some();
+
+        if let Some(bounds) = bounds {
+            let center = (bounds.min + bounds.max) * 0.5 * SceneBuilder::CELL_SIZE;
+            let size = (bounds.max - bounds.min) * SceneBuilder::CELL_SIZE;
+            transform.translation = center.extend(0.0);
+            transform.scale = size.max(Vec2::splat(5.0)).extend(1.0);
+        }
+    }
+}
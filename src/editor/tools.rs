@@ -0,0 +1,426 @@
+use bevy::prelude::*;
+
+use crate::core::{ButtonMode, Combobox, ComboboxType, ElevatorType, PlayerIndex};
+use crate::editor::Editor;
+use crate::levels::LevelObject;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorTool {
+    Select,
+    Wall,
+    Box,
+    Door,
+    Button,
+    Elevator,
+    Hint,
+    SpawnPoint,
+    FinishPoint,
+    Sensor,
+    GravityZone,
+}
+
+impl EditorTool {
+    const KEYS: [(KeyCode, EditorTool); 11] = [
+        (KeyCode::Q, EditorTool::Select),
+        (KeyCode::Key1, EditorTool::Wall),
+        (KeyCode::Key2, EditorTool::Box),
+        (KeyCode::Key3, EditorTool::Door),
+        (KeyCode::Key4, EditorTool::Button),
+        (KeyCode::Key5, EditorTool::Elevator),
+        (KeyCode::Key6, EditorTool::Hint),
+        (KeyCode::Key7, EditorTool::SpawnPoint),
+        (KeyCode::Key8, EditorTool::FinishPoint),
+        (KeyCode::Key9, EditorTool::Sensor),
+        (KeyCode::Key0, EditorTool::GravityZone),
+    ];
+
+    /// Number of variants cycled through with `T`.
+    pub fn variant_count(&self) -> usize {
+        match self {
+            EditorTool::Box => Self::BOX_TYPES.len(),
+            EditorTool::Button => Self::BUTTON_MODES.len() + Self::PLATE_WEIGHTS.len(),
+            EditorTool::Elevator => Self::ELEVATOR_TYPES.len(),
+            EditorTool::Hint => Self::HINT_IMAGES.len(),
+            EditorTool::SpawnPoint => Self::PLAYER_INDICES.len(),
+            _ => 1,
+        }
+    }
+
+    const BOX_TYPES: [&'static str; 11] = [
+        "standard", "x2", "undo", "up", "right", "down", "left", "gravity", "lamp", "split2",
+        "boots",
+    ];
+
+    fn box_type(variant: usize) -> ComboboxType {
+        match variant {
+            1 => ComboboxType::Buff(2.0),
+            2 => ComboboxType::Undo,
+            3 => ComboboxType::Direction { direction: Vec2::Y },
+            4 => ComboboxType::Direction { direction: Vec2::X },
+            5 => ComboboxType::Direction {
+                direction: Vec2::NEG_Y,
+            },
+            6 => ComboboxType::Direction {
+                direction: Vec2::NEG_X,
+            },
+            7 => ComboboxType::Gravity,
+            8 => ComboboxType::Lamp {
+                color: Color::WHITE,
+            },
+            9 => ComboboxType::Split(2),
+            10 => ComboboxType::Boots,
+            _ => ComboboxType::Standard { group: 0 },
+        }
+    }
+
+    /// Buttons come first, then pressure plates needing [`Self::PLATE_WEIGHTS`].
+    const BUTTON_MODES: [&'static str; 4] = ["button", "toggle", "latch", "timed"];
+
+    const PLATE_WEIGHTS: [f32; 3] = [2.0, 4.0, 9.0];
+
+    fn button_mode(variant: usize) -> ButtonMode {
+        match variant {
+            1 => ButtonMode::Toggle,
+            2 => ButtonMode::Latch { reset: None },
+            3 => ButtonMode::Timed { seconds: 3.0 },
+            _ => ButtonMode::Momentary,
+        }
+    }
+
+    const ELEVATOR_TYPES: [&'static str; 4] = ["loop", "triggered", "returning", "one shot"];
+
+    fn elevator_type(variant: usize) -> ElevatorType {
+        match variant {
+            1 | 2 => ElevatorType::Triggered {
+                seconds: 2.0,
+                returns: variant == 2,
+                progress: 0.0,
+            },
+            3 => ElevatorType::OneShot {
+                seconds: 2.0,
+                progress: 0.0,
+            },
+            _ => ElevatorType::Loop {
+                period: 4.0,
+                current: 0.0,
+            },
+        }
+    }
+
+    const HINT_IMAGES: [&'static str; 7] = [
+        "images/controls.png",
+        "images/controls-2.png",
+        "images/choose-wisely.png",
+        "images/code.png",
+        "images/enter-the-code.png",
+        "images/final.png",
+        "images/you-did-it.png",
+    ];
+
+    const PLAYER_INDICES: [PlayerIndex; 3] = [
+        PlayerIndex::SinglePlayer,
+        PlayerIndex::TwoPlayers(0),
+        PlayerIndex::TwoPlayers(1),
+    ];
+
+    pub fn variant_name(&self, variant: usize) -> String {
+        match self {
+            EditorTool::Box => Self::BOX_TYPES[variant].to_string(),
+            EditorTool::Button => match Self::BUTTON_MODES.get(variant) {
+                Some(mode) => mode.to_string(),
+                None => format!(
+                    "plate {}",
+                    Self::PLATE_WEIGHTS[variant - Self::BUTTON_MODES.len()]
+                ),
+            },
+            EditorTool::Elevator => Self::ELEVATOR_TYPES[variant].to_string(),
+            EditorTool::Hint => Self::HINT_IMAGES[variant].to_string(),
+            EditorTool::SpawnPoint => format!("{:?}", Self::PLAYER_INDICES[variant]),
+            _ => String::new(),
+        }
+    }
+}
+
+/// Changes applied to the selected object from the keyboard.
+#[derive(Debug, Clone, Copy)]
+pub enum ObjectEdit {
+    Delete,
+    Grow(f32),
+    Rotate,
+    /// Connects the object to the signal of an editor channel.
+    Connect(u32),
+    /// Optional signals are removed, objects that always need one keep theirs.
+    Disconnect,
+}
+
+/// Name of the signal behind an editor channel.
+pub fn channel_signal(channel: u32) -> String {
+    format!("signal{}", channel)
+}
+
+impl Editor {
+    pub(super) fn new_object(&self, position: Vec2) -> LevelObject {
+        let (x, y) = (position.x, position.y);
+        let signal = channel_signal(self.channel);
+
+        match self.tool {
+            EditorTool::Select | EditorTool::Wall => LevelObject::Wall {
+                left: x,
+                right: x,
+                bottom: y,
+                top: y,
+            },
+            EditorTool::Box => LevelObject::Box {
+                x,
+                y,
+                combobox: Combobox::new(1.0, EditorTool::box_type(self.variant)),
+            },
+            EditorTool::Door => LevelObject::Door {
+                x,
+                y,
+                height: 3.0,
+                direction: self.direction,
+                signal,
+            },
+            EditorTool::Button => match self.variant.checked_sub(EditorTool::BUTTON_MODES.len()) {
+                Some(plate) => LevelObject::PressurePlate {
+                    x,
+                    y,
+                    direction: self.direction,
+                    signal,
+                    min_weight: EditorTool::PLATE_WEIGHTS[plate],
+                    max_weight: None,
+                },
+                None => LevelObject::Button {
+                    x,
+                    y,
+                    direction: self.direction,
+                    signal,
+                    mode: EditorTool::button_mode(self.variant),
+                },
+            },
+            EditorTool::Elevator => LevelObject::Elevator {
+                start: position,
+                end: position,
+                elevator_type: EditorTool::elevator_type(self.variant),
+                // The others are set off by a signal
+                signal: (self.variant > 0).then_some(signal),
+            },
+            EditorTool::Hint => LevelObject::Hint {
+                x,
+                y,
+                image: EditorTool::HINT_IMAGES[self.variant].to_string(),
+            },
+            EditorTool::SpawnPoint => LevelObject::SpawnPoint {
+                x,
+                y,
+                index: EditorTool::PLAYER_INDICES[self.variant],
+            },
+            EditorTool::FinishPoint => LevelObject::FinishPoint { x, y, signal: None },
+            EditorTool::Sensor => LevelObject::Sensor {
+                left: x,
+                right: x,
+                bottom: y,
+                top: y,
+                signal,
+            },
+            EditorTool::GravityZone => LevelObject::GravityZone {
+                left: x,
+                right: x,
+                bottom: y,
+                top: y,
+                direction: self.direction,
+                strength: 1.0,
+            },
+        }
+    }
+}
+
+impl LevelObject {
+    /// Point that stays in place when the object is resized from `cursor`, `None` for
+    /// objects that can't be resized with the mouse.
+    pub(super) fn resize_anchor(&self, cursor: Vec2) -> Option<Vec2> {
+        match self {
+            LevelObject::Wall { .. }
+            | LevelObject::Sensor { .. }
+            | LevelObject::GravityZone { .. } => {
+                let bounds = self.bounds();
+                let center = (bounds.min + bounds.max) * 0.5;
+                Some(Vec2::new(
+                    if cursor.x < center.x {
+                        bounds.max.x
+                    } else {
+                        bounds.min.x
+                    },
+                    if cursor.y < center.y {
+                        bounds.max.y
+                    } else {
+                        bounds.min.y
+                    },
+                ))
+            }
+            LevelObject::Elevator { start, end, .. } => {
+                if start.distance(cursor) >= end.distance(cursor) {
+                    Some(*start)
+                } else {
+                    Some(*end)
+                }
+            }
+            _ => None,
+        }
+    }
+
+    pub(super) fn resize(&mut self, anchor: Vec2, to: Vec2) {
+        match self {
+            LevelObject::Wall {
+                left,
+                right,
+                bottom,
+                top,
+            }
+            | LevelObject::Sensor {
+                left,
+                right,
+                bottom,
+                top,
+                ..
+            }
+            | LevelObject::GravityZone {
+                left,
+                right,
+                bottom,
+                top,
+                ..
+            } => {
+                *left = anchor.x.min(to.x);
+                *right = anchor.x.max(to.x);
+                *bottom = anchor.y.min(to.y);
+                *top = anchor.y.max(to.y);
+            }
+            LevelObject::Elevator { start, end, .. } => {
+                if *start == anchor {
+                    *end = to;
+                } else {
+                    *start = to;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Walls, sensors and zones without area are left behind by a click without a drag.
+    pub(super) fn is_empty(&self) -> bool {
+        match self {
+            LevelObject::Wall {
+                left,
+                right,
+                bottom,
+                top,
+            }
+            | LevelObject::Sensor {
+                left,
+                right,
+                bottom,
+                top,
+                ..
+            }
+            | LevelObject::GravityZone {
+                left,
+                right,
+                bottom,
+                top,
+                ..
+            } => left == right || bottom == top,
+            _ => false,
+        }
+    }
+
+    pub(super) fn apply(&mut self, edit: ObjectEdit) {
+        match (self, edit) {
+            (LevelObject::Box { combobox, .. }, ObjectEdit::Grow(amount)) => {
+                combobox.weight = ((combobox.weight + amount) * 10.0).round().max(1.0) / 10.0;
+            }
+            (LevelObject::Door { height, .. }, ObjectEdit::Grow(amount)) => {
+                *height = (*height + amount.signum() * 0.5).max(0.5);
+            }
+            (
+                LevelObject::Elevator {
+                    elevator_type: ElevatorType::Loop { period, .. },
+                    ..
+                },
+                ObjectEdit::Grow(amount),
+            ) => {
+                *period = (*period + amount.signum()).max(1.0);
+            }
+            (
+                LevelObject::Elevator {
+                    elevator_type:
+                        ElevatorType::Triggered { seconds, .. } | ElevatorType::OneShot { seconds, .. },
+                    ..
+                },
+                ObjectEdit::Grow(amount),
+            ) => {
+                *seconds = (*seconds + amount.signum() * 0.5).max(0.5);
+            }
+            (
+                LevelObject::Button {
+                    mode: ButtonMode::Timed { seconds },
+                    ..
+                },
+                ObjectEdit::Grow(amount),
+            ) => {
+                *seconds = (*seconds + amount.signum() * 0.5).max(0.5);
+            }
+            (LevelObject::PressurePlate { min_weight, .. }, ObjectEdit::Grow(amount)) => {
+                *min_weight = (*min_weight + amount.signum()).max(1.0);
+            }
+            (LevelObject::GravityZone { strength, .. }, ObjectEdit::Grow(amount)) => {
+                *strength = (*strength + amount.signum() * 0.25).max(0.0);
+            }
+            (
+                LevelObject::Door { direction, .. }
+                | LevelObject::Button { direction, .. }
+                | LevelObject::PressurePlate { direction, .. }
+                | LevelObject::GravityZone { direction, .. },
+                ObjectEdit::Rotate,
+            ) => {
+                *direction = direction.get_perp();
+            }
+            (
+                LevelObject::Button { signal, .. }
+                | LevelObject::PressurePlate { signal, .. }
+                | LevelObject::Door { signal, .. }
+                | LevelObject::Sensor { signal, .. }
+                | LevelObject::Dispenser { signal, .. },
+                ObjectEdit::Connect(channel),
+            ) => {
+                *signal = channel_signal(channel);
+            }
+            (
+                LevelObject::Light { signal, .. }
+                | LevelObject::Elevator { signal, .. }
+                | LevelObject::FinishPoint { signal, .. },
+                edit @ (ObjectEdit::Connect(_) | ObjectEdit::Disconnect),
+            ) => {
+                *signal = match edit {
+                    ObjectEdit::Connect(channel) => Some(channel_signal(channel)),
+                    _ => None,
+                };
+            }
+            _ => {}
+        }
+    }
+}
+
+pub(super) fn choose_tool(mut editor: ResMut<Editor>, keys: Res<Input<KeyCode>>) {
+    for (key, tool) in EditorTool::KEYS {
+        if keys.just_pressed(key) {
+            editor.tool = tool;
+            editor.variant = 0;
+        }
+    }
+
+    if keys.just_pressed(KeyCode::T) {
+        editor.variant = (editor.variant + 1) % editor.tool.variant_count();
+    }
+}
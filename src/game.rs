@@ -1,10 +1,12 @@
 use bevy::prelude::*;
 
+use bevy::render::camera::CameraRenderGraph;
 use bevy::render::RenderApp;
 use post_processing::AmbientLight;
 use std::env;
 
-use crate::core::CorePlugin;
+use crate::core::{CorePlugin, GameCamera};
+use crate::editor::EditorPlugin;
 use crate::gui::GuiPlugin;
 use crate::levels::LevelPlugin;
 use crate::states::{AudioState, CameraState, GuiState, LevelState};
@@ -36,6 +38,7 @@ impl Plugin for ComboboxGamePlugin {
         app.add_plugin(LevelPlugin);
         app.add_plugin(CorePlugin);
         app.add_plugin(GuiPlugin);
+        app.add_plugin(EditorPlugin);
 
         let render_app = match app.get_sub_app_mut(RenderApp) {
             Ok(render_app) => render_app,
@@ -46,8 +49,29 @@ impl Plugin for ComboboxGamePlugin {
 }
 
 fn setup_camera(mut commands: Commands, _clear_color: ResMut<ClearColor>) {
+    // The second one is only used while the screen is split between two players
+    for index in 0..2 {
+        commands
+            .spawn_bundle(Camera2dBundle {
+                camera: Camera {
+                    priority: index as isize,
+                    is_active: index == 0,
+                    ..default()
+                },
+                transform: Transform::default().with_scale(Vec3::splat(1.0)),
+                ..default()
+            })
+            .insert(GameCamera { index })
+            .insert(UiCameraConfig { show_ui: false });
+    }
+
+    // Draws the UI over the whole window, split or not
     commands.spawn_bundle(Camera2dBundle {
-        transform: Transform::default().with_scale(Vec3::splat(1.0)),
+        camera: Camera {
+            priority: 2,
+            ..default()
+        },
+        camera_render_graph: CameraRenderGraph::new(post_processing::UI_GRAPH),
         ..default()
     });
 }
@@ -1,3 +1,4 @@
+use bevy::ecs::system::EntityCommands;
 use bevy::prelude::*;
 
 pub const BASE_ARROW_COLOR: UiColor = UiColor(Color::WHITE);
@@ -66,19 +67,22 @@ pub fn spawn_basic_button<T: Component>(
         .insert(button_type);
 }
 
-pub fn spawn_level_button<T: Component>(parent: &mut ChildBuilder, image: UiImage, button_type: T) {
-    parent
-        .spawn_bundle(ButtonBundle {
-            style: Style {
-                size: Size::new(Val::Undefined, Val::Percent(100.0)),
-                aspect_ratio: Some(1.0),
-                min_size: Size::new(Val::Px(50.0), Val::Px(50.0)),
-                ..default()
-            },
-            image,
-            color: BASE_ARROW_COLOR,
+pub fn spawn_level_button<'w, 's, 'a, T: Component>(
+    parent: &'a mut ChildBuilder<'w, 's, '_>,
+    image: UiImage,
+    button_type: T,
+) -> EntityCommands<'w, 's, 'a> {
+    let mut button = parent.spawn_bundle(ButtonBundle {
+        style: Style {
+            size: Size::new(Val::Undefined, Val::Percent(100.0)),
+            aspect_ratio: Some(1.0),
+            min_size: Size::new(Val::Px(50.0), Val::Px(50.0)),
             ..default()
-        })
-        .insert(MenuButton)
-        .insert(button_type);
+        },
+        image,
+        color: BASE_ARROW_COLOR,
+        ..default()
+    });
+    button.insert(MenuButton).insert(button_type);
+    button
 }
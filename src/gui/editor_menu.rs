@@ -0,0 +1,72 @@
+use bevy::prelude::*;
+
+use crate::editor::{channel_signal, Editor};
+use crate::states::GuiState;
+
+#[derive(Debug, Clone)]
+pub struct EditorGUIPlugin;
+
+impl Plugin for EditorGUIPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_enter(GuiState::Editor).with_system(setup));
+        app.add_system_set(SystemSet::on_update(GuiState::Editor).with_system(update));
+        app.add_system_set(SystemSet::on_exit(GuiState::Editor).with_system(cleanup));
+    }
+}
+
+const HELP: &str = "Q select, 1-9 place, T variant, R rotate, Del delete, +/- size, \
+    [/] channel, P/N connect/disconnect signal, Shift+drag resize, right drag pan, wheel zoom, \
+    Ctrl+S save, F2 play, Esc back";
+
+#[derive(Component)]
+pub struct EditorMenuNode;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let style = TextStyle {
+        font: asset_server.load("fonts/roboto.ttf"),
+        font_size: 18.0,
+        color: Color::WHITE,
+    };
+
+    commands
+        .spawn_bundle(
+            TextBundle::from_sections([
+                TextSection::new("", style.clone()),
+                TextSection::new(HELP, style),
+            ])
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Px(10.0),
+                    top: Val::Px(10.0),
+                    ..default()
+                },
+                max_size: Size::new(Val::Px(600.0), Val::Undefined),
+                ..default()
+            }),
+        )
+        .insert(EditorMenuNode);
+}
+
+fn update(editor: Res<Editor>, mut texts: Query<&mut Text, With<EditorMenuNode>>) {
+    if !editor.is_changed() {
+        return;
+    }
+
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = format!(
+            "{}\nTool: {:?} {}\nDirection: {:?}, signal: {}\n\n",
+            editor.status,
+            editor.tool,
+            editor.tool.variant_name(editor.variant),
+            editor.direction,
+            channel_signal(editor.channel),
+        );
+    }
+}
+
+fn cleanup(mut commands: Commands, nodes: Query<Entity, With<EditorMenuNode>>) {
+    for entity in nodes.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 
 use crate::gui::TRANSPARENT_COLOR;
+use crate::levels::LevelAttempt;
 use crate::states::{AudioState, CameraState, GuiState, LevelState};
 
 #[derive(Debug, Clone)]
@@ -26,11 +27,13 @@ fn interaction(
     mut audio_state: ResMut<State<AudioState>>,
     mut gui_state: ResMut<State<GuiState>>,
     mut camera_state: ResMut<State<CameraState>>,
+    mut attempt: ResMut<LevelAttempt>,
 ) {
     for (interaction, button) in interaction_query.iter() {
         match *interaction {
             Interaction::Clicked => match *button {
                 GameMenuButton::Restart => {
+                    attempt.stats.restarts += 1;
                     level_state.restart().unwrap();
                 }
                 GameMenuButton::Back => {
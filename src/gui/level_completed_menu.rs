@@ -1,7 +1,8 @@
 use bevy::prelude::*;
 
+use crate::gui::level_menu::format_time;
 use crate::gui::TRANSPARENT_COLOR;
-use crate::levels::CurrentLevel;
+use crate::levels::{CurrentLevel, LevelAttempt, LevelRegistry, Progress};
 use crate::states::{AudioState, CameraState, GuiState, LevelState};
 
 #[derive(Debug, Clone)]
@@ -32,6 +33,7 @@ fn interaction(
     mut gui_state: ResMut<State<GuiState>>,
     mut camera_state: ResMut<State<CameraState>>,
     mut current_level: ResMut<CurrentLevel>,
+    registry: Res<LevelRegistry>,
 ) {
     for (interaction, button) in interaction_query.iter() {
         match *interaction {
@@ -48,8 +50,8 @@ fn interaction(
                     camera_state.set(CameraState::None).unwrap();
                 }
                 LevelCompleteButton::NextLevel => {
-                    if current_level.level != 12 {
-                        current_level.level = (current_level.level) % 12 + 1;
+                    if let Some(next) = registry.next(&current_level.id) {
+                        current_level.id = next.id.clone();
                         level_state.restart().unwrap();
                         gui_state.set(GuiState::Level).unwrap();
                     } else {
@@ -68,7 +70,51 @@ fn interaction(
 #[derive(Component)]
 pub struct GameMenuNode;
 
-fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    attempt: Res<LevelAttempt>,
+    progress: Res<Progress>,
+    current_level: Res<CurrentLevel>,
+) {
+    let stats = &attempt.stats;
+    // `complete_level` may not have saved this attempt yet
+    let best_time = progress
+        .levels
+        .get(&current_level.id)
+        .map_or(stats.time, |level| level.best_time.min(stats.time));
+
+    commands
+        .spawn_bundle(
+            TextBundle::from_section(
+                format!(
+                    "Time: {}\nBest: {}\nRestarts: {}\nMerges: {}\nUndos: {}\nJumps: {}\nDistance: {:.0}",
+                    format_time(stats.time),
+                    format_time(best_time),
+                    stats.restarts,
+                    stats.merges,
+                    stats.undos,
+                    stats.jumps,
+                    stats.distance,
+                ),
+                TextStyle {
+                    font: asset_server.load("fonts/roboto.ttf"),
+                    font_size: 32.0,
+                    color: Color::WHITE,
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Percent(20.0),
+                    bottom: Val::Percent(20.0),
+                    ..default()
+                },
+                ..default()
+            }),
+        )
+        .insert(GameMenuNode);
+
     commands
         .spawn_bundle(ImageBundle {
             style: Style {
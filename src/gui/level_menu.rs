@@ -2,7 +2,7 @@ use bevy::prelude::*;
 
 use crate::gui::buttons::spawn_level_button;
 use crate::gui::TRANSPARENT_COLOR;
-use crate::levels::CurrentLevel;
+use crate::levels::{CurrentLevel, LevelRegistry, LevelStatus, Progress, SaveDir};
 use crate::states::{AudioState, CameraState, GuiState, LevelState};
 
 #[derive(Debug, Clone)]
@@ -10,14 +10,29 @@ pub struct LevelSelectionGUIPlugin;
 
 #[derive(Debug, Clone, Component)]
 pub enum LevelSelectionButton {
-    Level(usize),
+    Level(String),
     Back,
+    ResetProgress,
+}
+
+#[derive(Component)]
+pub struct ResetProgressText;
+
+/// Set on the reset button after its first click, a second one resets the progress. A rebuilt
+/// menu starts unarmed again.
+#[derive(Debug, Default, Component)]
+pub struct ResetConfirmation {
+    pub armed: bool,
 }
 
 impl Plugin for LevelSelectionGUIPlugin {
     fn build(&self, app: &mut App) {
         app.add_system_set(SystemSet::on_enter(GuiState::LevelSelection).with_system(setup));
-        app.add_system_set(SystemSet::on_update(GuiState::LevelSelection).with_system(interaction));
+        app.add_system_set(
+            SystemSet::on_update(GuiState::LevelSelection)
+                .with_system(interaction)
+                .with_system(refresh),
+        );
         app.add_system_set(SystemSet::on_exit(GuiState::LevelSelection).with_system(cleanup));
     }
 }
@@ -32,12 +47,23 @@ fn interaction(
     mut audio_state: ResMut<State<AudioState>>,
     mut camera_state: ResMut<State<CameraState>>,
     mut current_level: ResMut<CurrentLevel>,
+    mut progress: ResMut<Progress>,
+    save_dir: Res<SaveDir>,
+    mut texts: Query<&mut Text, With<ResetProgressText>>,
+    mut confirmations: Query<&mut ResetConfirmation>,
 ) {
     for (interaction, button) in interaction_query.iter() {
+        // Any other button takes back a reset waiting to be confirmed
+        if *interaction == Interaction::Clicked
+            && !matches!(button, LevelSelectionButton::ResetProgress)
+        {
+            arm_reset(false, &mut confirmations, &mut texts);
+        }
+
         match *interaction {
             Interaction::Clicked => match button {
                 LevelSelectionButton::Level(level) => {
-                    current_level.level = *level;
+                    current_level.id = level.clone();
 
                     level_state.set(LevelState::Level).unwrap();
                     audio_state.set(AudioState::Level).unwrap();
@@ -47,22 +73,95 @@ fn interaction(
                 LevelSelectionButton::Back => {
                     gui_state.set(GuiState::MainScreen).unwrap();
                 }
+                LevelSelectionButton::ResetProgress => {
+                    // Ask before throwing progress away
+                    let armed = confirmations.iter().any(|confirmation| confirmation.armed);
+                    if armed {
+                        progress.reset();
+                        progress.save(&save_dir);
+                    }
+                    arm_reset(!armed, &mut confirmations, &mut texts);
+                }
             },
             _ => {}
         }
     }
 }
 
+fn arm_reset(
+    armed: bool,
+    confirmations: &mut Query<&mut ResetConfirmation>,
+    texts: &mut Query<&mut Text, With<ResetProgressText>>,
+) {
+    for mut confirmation in confirmations.iter_mut() {
+        confirmation.armed = armed;
+    }
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = if armed {
+            "Click again to reset"
+        } else {
+            "Reset progress"
+        }
+        .to_string();
+    }
+}
+
 #[derive(Component)]
 pub struct LevelMenuNode;
 
+const LEVELS_PER_ROW: usize = 4;
+
 fn setup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    registry: Res<LevelRegistry>,
+    progress: Res<Progress>,
     mut clear_color: ResMut<ClearColor>,
 ) {
     clear_color.0 = Color::WHITE * 0.05;
 
+    spawn_menu(&mut commands, &asset_server, &registry, &progress);
+}
+
+/// Rebuilds the menu if the list of levels or the progress changes while it's shown.
+fn refresh(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    registry: Res<LevelRegistry>,
+    progress: Res<Progress>,
+    nodes: Query<Entity, With<LevelMenuNode>>,
+) {
+    let registry_changed = registry.is_changed() && !registry.is_added();
+    let progress_changed = progress.is_changed() && !progress.is_added();
+    if !registry_changed && !progress_changed {
+        return;
+    }
+
+    for entity in nodes.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    spawn_menu(&mut commands, &asset_server, &registry, &progress);
+}
+
+/// Minutes and seconds, e.g. `1:05.3`.
+pub fn format_time(seconds: f32) -> String {
+    format!("{}:{:04.1}", (seconds / 60.0) as u32, seconds % 60.0)
+}
+
+fn spawn_menu(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    registry: &LevelRegistry,
+    progress: &Progress,
+) {
+    let levels: Vec<_> = registry.iter().collect();
+    let rows = ((levels.len() + LEVELS_PER_ROW - 1) / LEVELS_PER_ROW).max(1);
+
+    // Two rows of padding and a row for the title
+    let total_rows = (rows + 3) as f32;
+    let row_height = 100.0 / total_rows;
+
     commands
         .spawn_bundle(NodeBundle {
             style: Style {
@@ -87,6 +186,34 @@ fn setup(
                     ..default()
                 })
                 .insert(LevelSelectionButton::Back);
+            parent
+                .spawn_bundle(ButtonBundle {
+                    style: Style {
+                        size: Size::new(Val::Undefined, Val::Percent(60.0)),
+                        min_size: Size::new(Val::Px(10.0), Val::Px(10.0)),
+                        margin: UiRect::new(Val::Auto, Val::Percent(1.0), Val::Auto, Val::Auto),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        aspect_ratio: Some(800.0 / 300.0),
+                        ..default()
+                    },
+                    image: asset_server.load("images/buttons/button.png").into(),
+                    ..default()
+                })
+                .insert(LevelSelectionButton::ResetProgress)
+                .insert(ResetConfirmation::default())
+                .with_children(|parent| {
+                    parent
+                        .spawn_bundle(TextBundle::from_section(
+                            "Reset progress",
+                            TextStyle {
+                                font: asset_server.load("fonts/roboto.ttf"),
+                                font_size: 20.0,
+                                color: Color::WHITE,
+                            },
+                        ))
+                        .insert(ResetProgressText);
+                });
         })
         .insert(LevelMenuNode);
 
@@ -97,19 +224,17 @@ fn setup(
                 margin: UiRect::all(Val::Auto),
                 size: Size::new(Val::Auto, Val::Percent(100.0)),
                 min_size: Size::new(Val::Px(50.0), Val::Px(50.0)),
-                aspect_ratio: Some(4.0 / 6.0),
+                aspect_ratio: Some(LEVELS_PER_ROW as f32 / total_rows),
                 ..default()
             },
             color: TRANSPARENT_COLOR,
             ..default()
         })
         .with_children(|parent| {
-            let mut level = 0;
-
             parent.spawn_bundle(NodeBundle {
                 style: Style {
                     flex_direction: FlexDirection::Row,
-                    size: Size::new(Val::Percent(100.0), Val::Percent(16.66)),
+                    size: Size::new(Val::Percent(100.0), Val::Percent(row_height)),
                     ..default()
                 },
                 color: TRANSPARENT_COLOR,
@@ -120,7 +245,7 @@ fn setup(
                 .spawn_bundle(NodeBundle {
                     style: Style {
                         flex_direction: FlexDirection::Row,
-                        size: Size::new(Val::Percent(100.0), Val::Percent(16.66)),
+                        size: Size::new(Val::Percent(100.0), Val::Percent(row_height)),
                         ..default()
                     },
                     color: TRANSPARENT_COLOR,
@@ -138,29 +263,63 @@ fn setup(
                     });
                 });
 
-            for _ in 0..3 {
+            for row in levels.chunks(LEVELS_PER_ROW) {
                 parent
                     .spawn_bundle(NodeBundle {
                         style: Style {
                             flex_direction: FlexDirection::Row,
                             margin: UiRect::all(Val::Auto),
-                            size: Size::new(Val::Percent(100.0), Val::Percent(16.66)),
+                            size: Size::new(Val::Percent(100.0), Val::Percent(row_height)),
                             ..default()
                         },
                         color: TRANSPARENT_COLOR,
                         ..default()
                     })
                     .with_children(|parent| {
-                        for _ in 0..4 {
-                            level += 1;
-                            let image = asset_server.load(
-                                format!("images/buttons/levels/level-{}.png", level).as_str(),
-                            );
-                            spawn_level_button(
-                                parent,
-                                image.into(),
-                                LevelSelectionButton::Level(level),
-                            );
+                        for info in row {
+                            let image = asset_server.load(info.thumbnail.as_str());
+
+                            match progress.status(registry, &info.id) {
+                                LevelStatus::Locked => {
+                                    spawn_locked_level(parent, image.into());
+                                }
+                                LevelStatus::Unlocked => {
+                                    spawn_level_button(
+                                        parent,
+                                        image.into(),
+                                        LevelSelectionButton::Level(info.id.clone()),
+                                    );
+                                }
+                                LevelStatus::Completed => {
+                                    let best_time = progress.levels[&info.id].best_time;
+                                    spawn_level_button(
+                                        parent,
+                                        image.into(),
+                                        LevelSelectionButton::Level(info.id.clone()),
+                                    )
+                                    .with_children(|parent| {
+                                        parent.spawn_bundle(
+                                            TextBundle::from_section(
+                                                format_time(best_time),
+                                                TextStyle {
+                                                    font: asset_server.load("fonts/roboto.ttf"),
+                                                    font_size: 18.0,
+                                                    color: Color::WHITE,
+                                                },
+                                            )
+                                            .with_style(Style {
+                                                position_type: PositionType::Absolute,
+                                                position: UiRect {
+                                                    bottom: Val::Percent(6.0),
+                                                    right: Val::Percent(12.0),
+                                                    ..default()
+                                                },
+                                                ..default()
+                                            }),
+                                        );
+                                    });
+                                }
+                            }
                         }
                     });
             }
@@ -168,7 +327,7 @@ fn setup(
             parent.spawn_bundle(NodeBundle {
                 style: Style {
                     flex_direction: FlexDirection::Row,
-                    size: Size::new(Val::Percent(100.0), Val::Percent(16.66)),
+                    size: Size::new(Val::Percent(100.0), Val::Percent(row_height)),
                     ..default()
                 },
                 color: TRANSPARENT_COLOR,
@@ -178,6 +337,21 @@ fn setup(
         .insert(LevelMenuNode);
 }
 
+/// Same as a level button, but darkened and not clickable.
+fn spawn_locked_level(parent: &mut ChildBuilder, image: UiImage) {
+    parent.spawn_bundle(ImageBundle {
+        style: Style {
+            size: Size::new(Val::Undefined, Val::Percent(100.0)),
+            aspect_ratio: Some(1.0),
+            min_size: Size::new(Val::Px(50.0), Val::Px(50.0)),
+            ..default()
+        },
+        image,
+        color: UiColor(Color::rgb(0.25, 0.25, 0.25)),
+        ..default()
+    });
+}
+
 fn cleanup(mut commands: Commands, nodes: Query<Entity, With<LevelMenuNode>>) {
     for entity in nodes.iter() {
         commands.entity(entity).despawn_recursive();
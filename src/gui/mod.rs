@@ -2,12 +2,14 @@ use bevy::prelude::*;
 
 use crate::gui::buttons::ButtonsPlugin;
 use crate::gui::credits_menu::CreditsGUIPlugin;
+use crate::gui::editor_menu::EditorGUIPlugin;
 use crate::gui::game_menu::GameMenuPlugin;
 use crate::gui::level_completed_menu::LevelCompleteGUIPlugin;
 use crate::gui::level_menu::LevelSelectionGUIPlugin;
 use crate::gui::main_menu::MainScreenGUIPlugin;
 
 mod buttons;
+mod editor_menu;
 mod game_menu;
 mod level_completed_menu;
 mod level_menu;
@@ -27,5 +29,6 @@ impl Plugin for GuiPlugin {
         app.add_plugin(GameMenuPlugin);
         app.add_plugin(LevelCompleteGUIPlugin);
         app.add_plugin(CreditsGUIPlugin);
+        app.add_plugin(EditorGUIPlugin);
     }
 }
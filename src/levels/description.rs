@@ -0,0 +1,447 @@
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::sprite::Rect;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::core::{
+    ButtonMode, Combobox, Elevator, ElevatorType, Gate, MergeRecipe, PlayerIndex, SceneBuilder,
+};
+use crate::utils::SceneDirection;
+
+/// Serialized level layout, loaded from `assets/levels/*.level.ron`.
+///
+/// All coordinates are in cells (see [`SceneBuilder::CELL_SIZE`]), exactly as
+/// they are passed to the `*_xy` methods of [`SceneBuilder`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TypeUuid)]
+#[uuid = "5c1d8a5e-3f0b-4d6a-9b0e-6f7f2f2a9c41"]
+pub struct LevelDescription {
+    #[serde(default)]
+    pub audio: Option<String>,
+    #[serde(default)]
+    pub background_color: Option<Color>,
+    #[serde(default)]
+    pub ambient_light: Option<Color>,
+    #[serde(default)]
+    pub boundaries: Option<LevelBoundaries>,
+    #[serde(default)]
+    pub min_view_range: Option<f32>,
+    /// Whether the view turns with gravity, regardless of what the player chose.
+    #[serde(default)]
+    pub rotate_camera: Option<bool>,
+    /// Keeps the finish points in view together with the players.
+    #[serde(default)]
+    pub frame_finish: bool,
+    #[serde(default)]
+    pub objects: Vec<LevelObject>,
+    /// Merge recipes only this level uses, tried before the shared ones.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recipes: Vec<MergeRecipe>,
+    /// Gates between the signals of buttons, sensors, doors and the other objects.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub logic: Vec<Gate>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LevelBoundaries {
+    pub left: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub top: f32,
+}
+
+/// Objects are spawned in the order they are listed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LevelObject {
+    Wall {
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+    },
+    Box {
+        x: f32,
+        y: f32,
+        combobox: Combobox,
+    },
+    Door {
+        x: f32,
+        y: f32,
+        height: f32,
+        direction: SceneDirection,
+        signal: String,
+    },
+    Button {
+        x: f32,
+        y: f32,
+        direction: SceneDirection,
+        signal: String,
+        #[serde(default, skip_serializing_if = "ButtonMode::is_momentary")]
+        mode: ButtonMode,
+    },
+    /// A button pressed by the total weight of the boxes and players on it.
+    PressurePlate {
+        x: f32,
+        y: f32,
+        direction: SceneDirection,
+        signal: String,
+        min_weight: f32,
+        #[serde(default)]
+        max_weight: Option<f32>,
+    },
+    /// Turns its signal on while a player or a box is inside.
+    Sensor {
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+        signal: String,
+    },
+    /// Players and boxes inside fall towards `direction`, `strength` times as fast as usual.
+    GravityZone {
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+        direction: SceneDirection,
+        strength: f32,
+    },
+    /// Drops a copy of the box every time the signal turns on.
+    Dispenser {
+        x: f32,
+        y: f32,
+        combobox: Combobox,
+        signal: String,
+    },
+    /// Lit while the signal is on, or always without one. The radius is in cells.
+    Light {
+        x: f32,
+        y: f32,
+        color: Color,
+        radius: f32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        signal: Option<String>,
+    },
+    Elevator {
+        start: Vec2,
+        end: Vec2,
+        elevator_type: ElevatorType,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        signal: Option<String>,
+    },
+    Hint {
+        x: f32,
+        y: f32,
+        image: String,
+    },
+    SpawnPoint {
+        x: f32,
+        y: f32,
+        index: PlayerIndex,
+    },
+    FinishPoint {
+        x: f32,
+        y: f32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        signal: Option<String>,
+    },
+}
+
+impl LevelDescription {
+    /// Serializes the level the same way the files in `assets/levels` are written: settings
+    /// are spread over several lines and every object takes exactly one line.
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        let config = PrettyConfig::new()
+            .depth_limit(2)
+            .decimal_floats(true)
+            .indentor("    ".to_string());
+        ron::ser::to_string_pretty(self, config).map(|s| s + "\n")
+    }
+
+    pub fn build(&self, builder: &mut SceneBuilder) {
+        if let Some(audio) = &self.audio {
+            builder.set_audio(audio);
+        }
+
+        if let Some(boundaries) = self.boundaries {
+            builder.set_boundaries(
+                boundaries.left,
+                boundaries.right,
+                boundaries.bottom,
+                boundaries.top,
+            );
+        }
+
+        if let Some(range) = self.min_view_range {
+            builder.set_min_view_range(range);
+        }
+
+        builder.set_frame_finish(self.frame_finish);
+
+        if let Some(rotate) = self.rotate_camera {
+            builder.set_camera_rotation(rotate);
+        }
+
+        if let Some(color) = self.background_color {
+            builder.set_background_color(color);
+        }
+
+        if let Some(color) = self.ambient_light {
+            builder.set_ambient_light(color);
+        }
+
+        for object in self.objects.iter() {
+            object.build(builder);
+        }
+    }
+}
+
+impl LevelObject {
+    /// Approximate area covered by the object, in cells.
+    pub fn bounds(&self) -> Rect {
+        let (center, size) = match self {
+            LevelObject::Wall {
+                left,
+                right,
+                bottom,
+                top,
+            }
+            | LevelObject::Sensor {
+                left,
+                right,
+                bottom,
+                top,
+                ..
+            }
+            | LevelObject::GravityZone {
+                left,
+                right,
+                bottom,
+                top,
+                ..
+            } => {
+                let from = Vec2::new(*left, *bottom);
+                let to = Vec2::new(*right, *top);
+                return Rect {
+                    min: from.min(to),
+                    max: from.max(to),
+                };
+            }
+            LevelObject::Box { x, y, combobox } | LevelObject::Dispenser { x, y, combobox, .. } => {
+                (
+                    Vec2::new(*x, *y),
+                    Vec2::ONE * combobox.world_size() / SceneBuilder::CELL_SIZE,
+                )
+            }
+            LevelObject::Door {
+                x,
+                y,
+                height,
+                direction,
+                ..
+            } => (
+                Vec2::new(*x, *y),
+                (direction.get_vec() * *height + direction.get_perp().get_vec() * 0.6).abs(),
+            ),
+            LevelObject::Button {
+                x, y, direction, ..
+            }
+            | LevelObject::PressurePlate {
+                x, y, direction, ..
+            } => (
+                Vec2::new(*x, *y) - direction.get_vec() * 0.3,
+                (direction.get_vec() * 0.4 + direction.get_perp().get_vec() * 1.1).abs(),
+            ),
+            LevelObject::Elevator {
+                start,
+                end,
+                elevator_type,
+                ..
+            } => {
+                let half_width = Vec2::ONE * Elevator::WIDTH * 0.5 / SceneBuilder::CELL_SIZE;
+                let (mut min, mut max) = (start.min(*end), start.max(*end));
+                if let ElevatorType::Path { waypoints, .. } = elevator_type {
+                    for waypoint in waypoints {
+                        min = min.min(waypoint.position);
+                        max = max.max(waypoint.position);
+                    }
+                }
+                return Rect {
+                    min: min - half_width,
+                    max: max + half_width,
+                };
+            }
+            LevelObject::Light { x, y, .. } => (Vec2::new(*x, *y), Vec2::new(0.5, 0.5)),
+            LevelObject::Hint { x, y, .. } => (Vec2::new(*x, *y), Vec2::new(6.5, 2.0)),
+            LevelObject::SpawnPoint { x, y, .. } => (Vec2::new(*x, *y), Vec2::new(1.0, 1.8)),
+            LevelObject::FinishPoint { x, y, .. } => (Vec2::new(*x, *y), Vec2::new(4.0, 4.0)),
+        };
+
+        Rect {
+            min: center - size * 0.5,
+            max: center + size * 0.5,
+        }
+    }
+
+    pub fn translate(&mut self, offset: Vec2) {
+        match self {
+            LevelObject::Wall {
+                left,
+                right,
+                bottom,
+                top,
+            }
+            | LevelObject::Sensor {
+                left,
+                right,
+                bottom,
+                top,
+                ..
+            }
+            | LevelObject::GravityZone {
+                left,
+                right,
+                bottom,
+                top,
+                ..
+            } => {
+                *left += offset.x;
+                *right += offset.x;
+                *bottom += offset.y;
+                *top += offset.y;
+            }
+            LevelObject::Elevator {
+                start,
+                end,
+                elevator_type,
+                ..
+            } => {
+                *start += offset;
+                *end += offset;
+                if let ElevatorType::Path { waypoints, .. } = elevator_type {
+                    for waypoint in waypoints {
+                        waypoint.position += offset;
+                    }
+                }
+            }
+            LevelObject::Box { x, y, .. }
+            | LevelObject::Door { x, y, .. }
+            | LevelObject::Button { x, y, .. }
+            | LevelObject::PressurePlate { x, y, .. }
+            | LevelObject::Dispenser { x, y, .. }
+            | LevelObject::Light { x, y, .. }
+            | LevelObject::Hint { x, y, .. }
+            | LevelObject::SpawnPoint { x, y, .. }
+            | LevelObject::FinishPoint { x, y, .. } => {
+                *x += offset.x;
+                *y += offset.y;
+            }
+        }
+    }
+
+    pub fn build(&self, builder: &mut SceneBuilder) {
+        match self {
+            LevelObject::Wall {
+                left,
+                right,
+                bottom,
+                top,
+            } => {
+                builder.spawn_wall_from_to_xy(*left, *right, *bottom, *top);
+            }
+            LevelObject::Box { x, y, combobox } => {
+                builder.spawn_box_xy(combobox.clone(), *x, *y);
+            }
+            LevelObject::Door {
+                x,
+                y,
+                height,
+                direction,
+                signal,
+            } => {
+                builder.spawn_door_xy(*x, *y, *height, *direction, signal);
+            }
+            LevelObject::Button {
+                x,
+                y,
+                direction,
+                signal,
+                mode,
+            } => {
+                builder.spawn_button_xy(*x, *y, *direction, signal, mode.clone());
+            }
+            LevelObject::PressurePlate {
+                x,
+                y,
+                direction,
+                signal,
+                min_weight,
+                max_weight,
+            } => {
+                builder.spawn_pressure_plate_xy(
+                    *x,
+                    *y,
+                    *direction,
+                    signal,
+                    *min_weight,
+                    *max_weight,
+                );
+            }
+            LevelObject::Sensor {
+                left,
+                right,
+                bottom,
+                top,
+                signal,
+            } => {
+                builder.spawn_sensor_from_to_xy(*left, *right, *bottom, *top, signal);
+            }
+            LevelObject::GravityZone {
+                left,
+                right,
+                bottom,
+                top,
+                direction,
+                strength,
+            } => {
+                builder.spawn_gravity_zone_from_to_xy(
+                    *left, *right, *bottom, *top, *direction, *strength,
+                );
+            }
+            LevelObject::Dispenser {
+                x,
+                y,
+                combobox,
+                signal,
+            } => {
+                builder.spawn_dispenser_xy(combobox.clone(), *x, *y, signal);
+            }
+            LevelObject::Light {
+                x,
+                y,
+                color,
+                radius,
+                signal,
+            } => {
+                builder.spawn_light_xy(*x, *y, *color, *radius, signal.as_deref());
+            }
+            LevelObject::Elevator {
+                start,
+                end,
+                elevator_type,
+                signal,
+            } => {
+                builder.spawn_elevator(*start, *end, elevator_type.clone(), signal.as_deref());
+            }
+            LevelObject::Hint { x, y, image } => {
+                builder.spawn_hint_xy(*x, *y, image);
+            }
+            LevelObject::SpawnPoint { x, y, index } => {
+                builder.set_spawn_point_xy(*x, *y, *index);
+            }
+            LevelObject::FinishPoint { x, y, signal } => {
+                builder.set_finish_point_xy(*x, *y, signal.as_deref());
+            }
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::path::Path;
+
+use bevy::asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset};
+
+use crate::core::{MergeRecipes, DEFAULT_RECIPES_PATH};
+use crate::levels::{LevelDescription, LevelInfo, LevelList, LEVEL_LIST_PATH};
+
+#[derive(Default)]
+pub struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let description = ron::de::from_bytes::<LevelDescription>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(description));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+#[derive(Default)]
+pub struct LevelListLoader;
+
+impl AssetLoader for LevelListLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let list = ron::de::from_bytes::<LevelList>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(list));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["registry.ron"]
+    }
+}
+
+#[derive(Default)]
+pub struct RecipesLoader;
+
+impl AssetLoader for RecipesLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let recipes = ron::de::from_bytes::<MergeRecipes>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(recipes));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["recipes.ron"]
+    }
+}
+
+/// A registry entry with its level, or the reason the level couldn't be read.
+pub type LevelEntry = (LevelInfo, Result<LevelDescription, String>);
+
+/// Reads the level registry and every level it lists straight from the `assets` folder, for
+/// tools that run without an `AssetServer`.
+pub fn read_levels(assets: &Path) -> Result<Vec<LevelEntry>, String> {
+    let mut list = read_list(assets)?;
+    list.levels.sort_by_key(|info| info.order);
+
+    Ok(list
+        .levels
+        .into_iter()
+        .map(|info| {
+            let description = read_file(assets, &info.path).and_then(|bytes| {
+                ron::de::from_bytes::<LevelDescription>(&bytes)
+                    .map_err(|e| format!("{}: {}", info.path, e))
+            });
+            (info, description)
+        })
+        .collect())
+}
+
+/// The default merge recipes with the ones from the level registry on top, like the game
+/// uses them. Levels can still add their own.
+pub fn read_recipes(assets: &Path) -> Result<MergeRecipes, String> {
+    let bytes = read_file(assets, DEFAULT_RECIPES_PATH)?;
+    let recipes = ron::de::from_bytes::<MergeRecipes>(&bytes)
+        .map_err(|e| format!("{}: {}", DEFAULT_RECIPES_PATH, e))?;
+
+    Ok(recipes.with(&read_list(assets)?.recipes))
+}
+
+fn read_list(assets: &Path) -> Result<LevelList, String> {
+    let bytes = read_file(assets, LEVEL_LIST_PATH)?;
+    ron::de::from_bytes::<LevelList>(&bytes).map_err(|e| format!("{}: {}", LEVEL_LIST_PATH, e))
+}
+
+fn read_file(assets: &Path, path: &str) -> Result<Vec<u8>, String> {
+    std::fs::read(assets.join(path)).map_err(|e| format!("{}: {}", path, e))
+}
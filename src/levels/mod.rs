@@ -1,45 +1,127 @@
+use bevy::asset::LoadState;
+use bevy::input::InputSystem;
 use bevy::math::Vec3Swizzles;
 use bevy::prelude::*;
-use bevy_kira_audio::{Audio, AudioControl};
 use bevy_rapier2d::plugin::RapierConfiguration;
+use bevy_rapier2d::prelude::Velocity;
 use post_processing::AmbientLight;
 
 use crate::core::{
-    BackgroundMusic, FinishPoint, Material, Player, PlayerBundle, PlayerType, PlayersSettings,
-    SceneBoundaries, SceneBuilder, SpawnPoint, GRAVITY_FORCE,
+    BackgroundMusic, Circuit, FinishPoint, KinematicSystem, LevelFinished, Material, MergeRecipes,
+    Player, PlayerBundle, PlayerIndex, PlayerType, PlayersSettings, SceneBoundaries, SceneBuilder,
+    SignalSystem, SimulationApp, SimulationStep, SpawnPoint, DEFAULT_RECIPES_PATH, FIXED_DT,
+    GRAVITY_FORCE,
 };
 
 use crate::states::{AudioState, CameraState, GuiState, LevelState};
 
-mod level1;
-mod level10;
-mod level11;
-mod level12;
-mod level2;
-mod level3;
-mod level4;
-mod level5;
-mod level6;
-mod level7;
-mod level8;
-mod level9;
+pub use description::*;
+pub use loader::*;
+pub use progress::*;
+pub use registry::*;
+pub use replay::*;
+pub use rewind::*;
+pub use stats::*;
+pub use validation::*;
+
+mod description;
+mod loader;
+mod progress;
+mod registry;
+mod replay;
+mod rewind;
+mod stats;
+mod validation;
 
 pub struct LevelPlugin;
 
 pub struct CurrentLevel {
-    pub level: usize,
+    pub id: String,
 }
 
 impl Plugin for LevelPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(CurrentLevel { level: 3 });
-        app.add_system_set(SystemSet::on_enter(LevelState::Level).with_system(setup));
-        app.add_system_set(SystemSet::on_exit(LevelState::Level).with_system(cleanup));
+        app.add_asset::<LevelDescription>();
+        app.add_asset::<LevelList>();
+        app.add_asset::<MergeRecipes>();
+        app.init_asset_loader::<LevelLoader>();
+        app.init_asset_loader::<LevelListLoader>();
+        app.init_asset_loader::<RecipesLoader>();
+
+        app.init_resource::<LevelRegistry>();
+        // The recipes of the level being played
+        app.init_resource::<MergeRecipes>();
+        // Picked by `update_registry` once the registry is loaded
+        app.insert_resource(CurrentLevel { id: String::new() });
+        // Tests insert their own, to keep away from the real save files
+        let save_dir = app
+            .world
+            .get_resource_or_insert_with(SaveDir::default)
+            .clone();
+        app.insert_resource(Progress::load(&save_dir));
+        app.init_resource::<LevelAttempt>();
+        app.init_resource::<Rewind>();
+        app.init_resource::<Replays>();
+        app.init_resource::<PreservedPlayers>();
+        app.add_startup_system(load_registry);
+        app.add_startup_system(load_players);
+        app.add_startup_system(load_camera_settings);
+        app.add_system(update_registry);
+        app.add_system(save_players);
+        app.add_system(save_camera_settings);
+        app.add_system_to_stage(CoreStage::PreUpdate, stop_playback.after(InputSystem));
+        app.add_simulation_system_set_to_stage(
+            SimulationStep::First,
+            SystemSet::on_update(LevelState::Level).with_system(play),
+        );
+        app.add_system_set(
+            SystemSet::on_enter(GuiState::LevelCompleted)
+                .with_system(complete_level)
+                .with_system(save_solution),
+        );
+        app.add_system_set(
+            SystemSet::on_enter(LevelState::Level)
+                .with_system(setup)
+                .with_system(start_attempt)
+                .with_system(start_recording),
+        );
+        app.add_system_set(SystemSet::on_enter(LevelState::None).with_system(end_attempt));
+        app.add_system_set(
+            SystemSet::on_exit(LevelState::Level)
+                .with_system(cleanup)
+                .with_system(stop_rewind),
+        );
         app.add_system_set(
             SystemSet::on_update(LevelState::Level)
+                .with_system(reload_level)
+                .with_system(build_level.after(reload_level))
                 .with_system(spawn_players)
                 .with_system(restart_on_out_of_boundaries)
-                .with_system(finish_level),
+                .with_system(count_merges)
+                .with_system(count_jumps)
+                .with_system(replay_keys),
+        );
+        app.add_simulation_system_set(
+            SystemSet::on_update(LevelState::Level)
+                .with_system(finish_level.after(SignalSystem))
+                .with_system(count_time)
+                .with_system(count_distance)
+                // After everything that stands still while rewinding
+                .with_system(rewind.after(KinematicSystem))
+                .with_system(record),
+        );
+
+        // The editor shows the same level, but without players
+        app.add_system_set(
+            SystemSet::on_enter(LevelState::Editor)
+                .with_system(setup)
+                .with_system(end_attempt),
+        );
+        app.add_system_set(SystemSet::on_exit(LevelState::Editor).with_system(cleanup));
+        app.add_system_set(
+            SystemSet::on_update(LevelState::Editor)
+                .with_system(reload_level)
+                .with_system(build_level.after(reload_level)),
         );
     }
 }
@@ -47,10 +129,19 @@ impl Plugin for LevelPlugin {
 #[derive(Component)]
 pub struct LevelRoot;
 
+/// Marks a [`LevelRoot`] whose [`LevelDescription`] hasn't been built yet.
+#[derive(Component)]
+pub struct PendingLevel;
+
+/// Players' state from before the level was rebuilt, restored by `spawn_players`.
+#[derive(Default)]
+pub struct PreservedPlayers(Vec<(PlayerIndex, Vec2, Velocity)>);
+
 fn restart_on_out_of_boundaries(
     players: Query<&GlobalTransform, With<Player>>,
     boundaries: Res<SceneBoundaries>,
     mut level_state: ResMut<State<LevelState>>,
+    mut attempt: ResMut<LevelAttempt>,
 ) {
     if !players.is_empty()
         && players.iter().all(|t| {
@@ -66,30 +157,32 @@ fn restart_on_out_of_boundaries(
             }
         })
     {
-
+        attempt.stats.restarts += 1;
         level_state.restart().unwrap();
     }
 }
 
 fn finish_level(
-    finish_points: Query<&GlobalTransform, With<FinishPoint>>,
+    finish_points: Query<(&GlobalTransform, &FinishPoint)>,
+    circuit: Res<Circuit>,
     players: Query<&GlobalTransform, With<Player>>,
     mut level_state: ResMut<State<LevelState>>,
     mut gui_state: ResMut<State<GuiState>>,
     mut camera_state: ResMut<State<CameraState>>,
     mut audio_state: ResMut<State<AudioState>>,
     mut timer: Local<f32>,
-    time: Res<Time>,
-    audio: Res<Audio>,
-    assets: Res<AssetServer>,
     mut ambient_light: ResMut<AmbientLight>,
+    current_level: Res<CurrentLevel>,
+    mut events: EventWriter<LevelFinished>,
 ) {
     let mut any_player_unfinished = false;
 
     for player in players.iter() {
         let mut finished = false;
-        for finish in finish_points.iter() {
-            if (player.translation().xy() - finish.translation().xy()).length() < 120.0 {
+        for (finish, finish_point) in finish_points.iter() {
+            if (player.translation().xy() - finish.translation().xy()).length() < 120.0
+                && circuit.allows(finish_point.signal.as_deref())
+            {
                 finished = true;
             }
         }
@@ -102,10 +195,12 @@ fn finish_level(
         && !any_player_unfinished
         && *gui_state.current() != GuiState::LevelCompleted
     {
-        *timer += time.delta_seconds();
+        *timer += FIXED_DT;
         if *timer > 1.0 {
             *timer = 0.0;
-            audio.play(assets.load("audio/finish.ogg")).with_volume(0.8);
+            events.send(LevelFinished {
+                level: current_level.id.clone(),
+            });
             gui_state.set(GuiState::LevelCompleted).unwrap();
             ambient_light.color = Color::WHITE * 0.3;
             //camera_state.set(CameraState::None).unwrap();
@@ -118,12 +213,13 @@ fn finish_level(
 
 fn spawn_players(
     mut commands: Commands,
-    spawn_points: Query<(Entity, &SpawnPoint)>,
+    spawn_points: Query<(Entity, &SpawnPoint, &Transform)>,
     players: Query<&Player>,
     asset_server: Res<AssetServer>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<Material>>,
     players_settings: Res<PlayersSettings>,
+    mut preserved_players: ResMut<PreservedPlayers>,
 ) {
     let num_of_players = players_settings
         .player_type
@@ -131,7 +227,7 @@ fn spawn_players(
         .filter(|v| **v != PlayerType::None)
         .count();
 
-    for (entity, spawn_point) in spawn_points.iter() {
+    for (entity, spawn_point, spawn_transform) in spawn_points.iter() {
         if spawn_point.index.get_number_of_players() != num_of_players {
             continue;
         }
@@ -153,30 +249,122 @@ fn spawn_players(
             );
             let player_id = commands.spawn_bundle(player_bundle).id();
             commands.entity(entity).add_child(player_id);
+
+            if let Some(i) = preserved_players
+                .0
+                .iter()
+                .position(|(index, _, _)| *index == spawn_point.index)
+            {
+                let (_, position, velocity) = preserved_players.0.remove(i);
+                let offset = position - spawn_transform.translation.truncate();
+                commands
+                    .entity(player_id)
+                    .insert(Transform::from_translation(
+                        offset.extend(SceneBuilder::PLAYER_DEPTH),
+                    ))
+                    .insert(velocity);
+            }
         }
     }
 }
 
-fn setup(
-    current_level: Res<CurrentLevel>,
+fn setup(mut commands: Commands) {
+    commands
+        .spawn()
+        .insert(LevelRoot)
+        .insert(PendingLevel)
+        .insert_bundle(VisibilityBundle::default())
+        .insert_bundle(TransformBundle::default());
+}
+
+fn reload_level(
     mut commands: Commands,
+    mut events: EventReader<AssetEvent<LevelDescription>>,
+    roots: Query<(Entity, &Handle<LevelDescription>), With<LevelRoot>>,
+    players: Query<(&Player, &GlobalTransform, &Velocity)>,
+    mut preserved_players: ResMut<PreservedPlayers>,
+) {
+    for event in events.iter() {
+        if let AssetEvent::Modified { handle } = event {
+            for (root, root_handle) in roots.iter() {
+                if root_handle != handle {
+                    continue;
+                }
+
+                preserved_players.0 = players
+                    .iter()
+                    .map(|(player, transform, velocity)| {
+                        (player.index, transform.translation().truncate(), *velocity)
+                    })
+                    .collect();
+
+                commands.entity(root).despawn_descendants();
+                commands.entity(root).insert(PendingLevel);
+            }
+        }
+    }
+}
+
+fn build_level(
+    mut commands: Commands,
+    roots: Query<Entity, (With<LevelRoot>, With<PendingLevel>)>,
+    current_level: Res<CurrentLevel>,
+    registry: Res<LevelRegistry>,
+    descriptions: Res<Assets<LevelDescription>>,
+    mut recipes: ResMut<Assets<MergeRecipes>>,
+    mut level_recipes: ResMut<MergeRecipes>,
+    mut circuit: ResMut<Circuit>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<Material>>,
     mut boundaries: ResMut<SceneBoundaries>,
     mut assets: ResMut<AssetServer>,
     mut config: ResMut<RapierConfiguration>,
-    mut ambient_light: ResMut<AmbientLight>,
+    ambient_light: ResMut<AmbientLight>,
     background_music: ResMut<BackgroundMusic>,
 ) {
+    // There is only one level at a time
+    let root = match roots.iter().next() {
+        Some(root) => root,
+        None => return,
+    };
+
+    let handle = match registry.get_handle(&current_level.id) {
+        Some(handle) => handle,
+        None => return,
+    };
+
+    let description = match descriptions.get(&handle) {
+        Some(description) => description,
+        None => return,
+    };
+
+    // Levels still play with the recipes the game was built with
+    let default_recipes = registry.default_recipes();
+    if assets.get_load_state(default_recipes) == LoadState::Failed
+        && recipes.get(default_recipes).is_none()
+    {
+        error!(
+            "Can't load {}, using the built-in merge recipes",
+            DEFAULT_RECIPES_PATH
+        );
+        recipes.set_untracked(default_recipes, MergeRecipes::builtin());
+    }
+
+    let shared_recipes = match registry.recipes(&recipes) {
+        Some(recipes) => recipes,
+        None => return,
+    };
+    *level_recipes = shared_recipes.with(&description.recipes);
+    *circuit = Circuit::new(&description.logic);
+
     // Set map defaults
     config.gravity = Vec2::NEG_Y * GRAVITY_FORCE;
     *boundaries = SceneBoundaries::default();
 
     commands
-        .spawn()
-        .insert(LevelRoot)
-        .insert_bundle(VisibilityBundle::default())
-        .insert_bundle(TransformBundle::default())
+        .entity(root)
+        .remove::<PendingLevel>()
+        .insert(handle.clone())
         .with_children(|parent| {
             let mut builder = SceneBuilder::new(
                 parent,
@@ -187,42 +375,7 @@ fn setup(
                 background_music,
                 ambient_light,
             );
-            if current_level.level == 1 {
-                level1::setup(&mut builder);
-            }
-            if current_level.level == 2 {
-                level2::setup(&mut builder);
-            }
-            if current_level.level == 3 {
-                level3::setup(&mut builder);
-            }
-            if current_level.level == 4 {
-                level4::setup(&mut builder);
-            }
-            if current_level.level == 5 {
-                level5::setup(&mut builder);
-            }
-            if current_level.level == 6 {
-                level6::setup(&mut builder);
-            }
-            if current_level.level == 7 {
-                level7::setup(&mut builder);
-            }
-            if current_level.level == 8 {
-                level8::setup(&mut builder);
-            }
-            if current_level.level == 9 {
-                level9::setup(&mut builder);
-            }
-            if current_level.level == 10 {
-                level10::setup(&mut builder);
-            }
-            if current_level.level == 11 {
-                level11::setup(&mut builder);
-            }
-            if current_level.level == 12 {
-                level12::setup(&mut builder);
-            }
+            description.build(&mut builder);
         });
 }
 
@@ -230,7 +383,10 @@ fn cleanup(
     mut commands: Commands,
     roots: Query<Entity, With<LevelRoot>>,
     mut clear_color: ResMut<ClearColor>,
+    mut preserved_players: ResMut<PreservedPlayers>,
 ) {
+    preserved_players.0.clear();
+
     for root in roots.iter() {
         commands.entity(root).despawn_recursive();
     }
@@ -0,0 +1,180 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::core::{CameraSettings, PlayerType, PlayersSettings, MAX_PLAYERS_NUM};
+use crate::levels::{CurrentLevel, LevelAttempt, LevelRegistry, LevelStats};
+
+/// Saved in the [`SaveDir`], e.g. `~/.config/combobox-game/progress.ron`.
+pub const PROGRESS_FILE: &str = "progress.ron";
+
+/// Everything that is remembered between launches.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Progress {
+    /// Completed levels by id.
+    #[serde(default)]
+    pub levels: BTreeMap<String, LevelProgress>,
+    #[serde(default)]
+    pub players: Option<[PlayerType; MAX_PLAYERS_NUM]>,
+    /// See [`CameraSettings::rotate_with_gravity`].
+    #[serde(default)]
+    pub rotate_camera: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LevelProgress {
+    /// Seconds.
+    pub best_time: f32,
+    /// The most recent completed attempt.
+    #[serde(default)]
+    pub last_attempt: LevelStats,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelStatus {
+    Locked,
+    Unlocked,
+    Completed,
+}
+
+impl Progress {
+    pub fn is_completed(&self, id: &str) -> bool {
+        self.levels.contains_key(id)
+    }
+
+    /// The first level is always unlocked, every other one once the level before it is completed.
+    pub fn status(&self, registry: &LevelRegistry, id: &str) -> LevelStatus {
+        if self.is_completed(id) {
+            return LevelStatus::Completed;
+        }
+
+        let previous = registry
+            .iter()
+            .take_while(|info| info.id != id)
+            .last()
+            .map(|info| info.id.as_str());
+
+        match previous {
+            Some(previous) if !self.is_completed(previous) => LevelStatus::Locked,
+            _ => LevelStatus::Unlocked,
+        }
+    }
+
+    /// Forgets completed levels, but keeps the chosen colors and settings.
+    pub fn reset(&mut self) {
+        self.levels.clear();
+    }
+
+    /// Missing or broken save files start from scratch.
+    pub fn load(save_dir: &SaveDir) -> Progress {
+        if let Some(path) = save_dir.path(PROGRESS_FILE) {
+            match std::fs::read(&path) {
+                Ok(bytes) => match ron::de::from_bytes(&bytes) {
+                    Ok(progress) => return progress,
+                    Err(error) => warn!("Can't read {}: {}", path.display(), error),
+                },
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+                Err(error) => warn!("Can't read {}: {}", path.display(), error),
+            }
+        }
+
+        Progress::default()
+    }
+
+    pub fn save(&self, save_dir: &SaveDir) {
+        if let Some(path) = save_dir.path(PROGRESS_FILE) {
+            let result = ron::ser::to_string_pretty(self, default())
+                .map_err(|e| e.to_string())
+                .and_then(|data| {
+                    if let Some(dir) = path.parent() {
+                        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+                    }
+                    std::fs::write(&path, data).map_err(|e| e.to_string())
+                });
+
+            if let Err(error) = result {
+                warn!("Can't save {}: {}", path.display(), error);
+            }
+        }
+    }
+}
+
+/// Where progress and replays are saved, nothing is saved without one. Defaults to the platform
+/// config directory, and to nothing in the browser.
+#[derive(Debug, Clone)]
+pub struct SaveDir(pub Option<PathBuf>);
+
+impl Default for SaveDir {
+    fn default() -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        return SaveDir(dirs::config_dir().map(|dir| dir.join(env!("CARGO_PKG_NAME"))));
+
+        #[cfg(target_arch = "wasm32")]
+        return SaveDir(None);
+    }
+}
+
+impl SaveDir {
+    /// `path` is relative to the save directory.
+    pub fn path(&self, path: impl AsRef<Path>) -> Option<PathBuf> {
+        self.0.as_ref().map(|dir| dir.join(path))
+    }
+}
+
+pub(super) fn load_players(progress: Res<Progress>, mut players_settings: ResMut<PlayersSettings>) {
+    if let Some(players) = progress.players {
+        players_settings.player_type = players;
+    }
+}
+
+pub(super) fn save_players(
+    mut progress: ResMut<Progress>,
+    players_settings: Res<PlayersSettings>,
+    save_dir: Res<SaveDir>,
+) {
+    if players_settings.is_changed() && progress.players != Some(players_settings.player_type) {
+        progress.players = Some(players_settings.player_type);
+        progress.save(&save_dir);
+    }
+}
+
+pub(super) fn load_camera_settings(
+    progress: Res<Progress>,
+    mut camera_settings: ResMut<CameraSettings>,
+) {
+    camera_settings.rotate_with_gravity = progress.rotate_camera;
+}
+
+pub(super) fn save_camera_settings(
+    mut progress: ResMut<Progress>,
+    camera_settings: Res<CameraSettings>,
+    save_dir: Res<SaveDir>,
+) {
+    if camera_settings.is_changed() && progress.rotate_camera != camera_settings.rotate_with_gravity
+    {
+        progress.rotate_camera = camera_settings.rotate_with_gravity;
+        progress.save(&save_dir);
+    }
+}
+
+pub(super) fn complete_level(
+    mut progress: ResMut<Progress>,
+    current_level: Res<CurrentLevel>,
+    mut attempt: ResMut<LevelAttempt>,
+    save_dir: Res<SaveDir>,
+) {
+    let level = progress
+        .levels
+        .entry(current_level.id.clone())
+        .or_insert(LevelProgress {
+            best_time: f32::INFINITY,
+            last_attempt: default(),
+        });
+    level.best_time = level.best_time.min(attempt.stats.time);
+    level.last_attempt = attempt.stats.clone();
+    attempt.end();
+
+    progress.save(&save_dir);
+}
@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use serde::{Deserialize, Serialize};
+
+use crate::core::{MergeRecipe, MergeRecipes, DEFAULT_RECIPES_PATH};
+use crate::levels::{CurrentLevel, LevelDescription, Progress};
+
+pub const LEVEL_LIST_PATH: &str = "levels/levels.registry.ron";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelInfo {
+    pub id: String,
+    pub name: String,
+    pub thumbnail: String,
+    /// Path of the `.level.ron` file, relative to the assets folder.
+    pub path: String,
+    /// Levels are listed by ascending order, ties are broken by position in the file.
+    #[serde(default)]
+    pub order: i32,
+}
+
+/// Contents of `assets/levels/levels.registry.ron`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TypeUuid)]
+#[uuid = "0b6f3c8e-6a4d-4f0e-8d7c-2a1e9b5f4c63"]
+pub struct LevelList {
+    pub levels: Vec<LevelInfo>,
+    /// Merge recipes for every level in the list, tried before the default ones.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recipes: Vec<MergeRecipe>,
+}
+
+/// All available levels, kept in sync with the [`LevelList`] file.
+#[derive(Default)]
+pub struct LevelRegistry {
+    list: Handle<LevelList>,
+    levels: Vec<(LevelInfo, Handle<LevelDescription>)>,
+    default_recipes: Handle<MergeRecipes>,
+    recipes: Vec<MergeRecipe>,
+}
+
+impl LevelRegistry {
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &LevelInfo> {
+        self.levels.iter().map(|(info, _)| info)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&LevelInfo> {
+        self.iter().find(|info| info.id == id)
+    }
+
+    pub fn get_handle(&self, id: &str) -> Option<Handle<LevelDescription>> {
+        self.levels
+            .iter()
+            .find(|(info, _)| info.id == id)
+            .map(|(_, handle)| handle.clone())
+    }
+
+    pub fn first(&self) -> Option<&LevelInfo> {
+        self.iter().next()
+    }
+
+    pub fn next(&self, id: &str) -> Option<&LevelInfo> {
+        self.iter().skip_while(|info| info.id != id).nth(1)
+    }
+
+    pub fn default_recipes(&self) -> &Handle<MergeRecipes> {
+        &self.default_recipes
+    }
+
+    /// Merge recipes shared by all levels, `None` until the defaults are loaded.
+    pub fn recipes(&self, recipes: &Assets<MergeRecipes>) -> Option<MergeRecipes> {
+        recipes
+            .get(&self.default_recipes)
+            .map(|defaults| defaults.with(&self.recipes))
+    }
+
+    /// Adds a level that isn't in the list file, e.g. one made in code. It is forgotten when
+    /// the list file is reloaded.
+    pub fn insert(&mut self, info: LevelInfo, handle: Handle<LevelDescription>) {
+        self.levels.retain(|(other, _)| other.id != info.id);
+        self.levels.push((info, handle));
+    }
+}
+
+pub(super) fn load_registry(mut registry: ResMut<LevelRegistry>, assets: Res<AssetServer>) {
+    registry.list = assets.load(LEVEL_LIST_PATH);
+    registry.default_recipes = assets.load(DEFAULT_RECIPES_PATH);
+}
+
+pub(super) fn update_registry(
+    mut events: EventReader<AssetEvent<LevelList>>,
+    mut registry: ResMut<LevelRegistry>,
+    mut current_level: ResMut<CurrentLevel>,
+    lists: Res<Assets<LevelList>>,
+    assets: Res<AssetServer>,
+    progress: Res<Progress>,
+) {
+    for event in events.iter() {
+        match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+                if *handle != registry.list {
+                    continue;
+                }
+
+                if let Some(list) = lists.get(handle) {
+                    let mut levels = list.levels.clone();
+                    levels.sort_by_key(|info| info.order);
+
+                    // Keep every level loaded, so switching levels doesn't wait for the disk
+                    registry.levels = levels
+                        .into_iter()
+                        .map(|info| {
+                            let handle = assets.load(info.path.as_str());
+                            (info, handle)
+                        })
+                        .collect();
+                    registry.recipes = list.recipes.clone();
+                }
+
+                // Continue from the first level that isn't completed yet
+                if registry.get(&current_level.id).is_none() {
+                    let next = registry
+                        .iter()
+                        .find(|info| !progress.is_completed(&info.id))
+                        .or_else(|| registry.first());
+
+                    if let Some(next) = next {
+                        current_level.id = next.id.clone();
+                    }
+                }
+            }
+            AssetEvent::Removed { .. } => {}
+        }
+    }
+}
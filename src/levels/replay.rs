@@ -0,0 +1,290 @@
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::core::{Player, ScreenDown, ROTATE_CAMERA_KEY};
+use crate::levels::{CurrentLevel, SaveDir};
+use crate::states::LevelState;
+
+/// Saved in the [`SaveDir`], e.g. `~/.config/combobox-game/replays/`.
+pub const REPLAY_DIR: &str = "replays";
+
+/// Saves the run so far, for bug reports.
+pub const SAVE_REPLAY_KEY: KeyCode = KeyCode::F9;
+
+/// Plays the last finished run of the current level, or stops playing it.
+pub const WATCH_REPLAY_KEY: KeyCode = KeyCode::F10;
+
+/// Keys held during every simulation step of a level run, starting when the players are
+/// spawned. Together with the fixed timestep this is enough to play the run again.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Replay {
+    pub level: String,
+    /// Runs of identical steps, as the number of steps and the keys held.
+    pub frames: Vec<(u32, Vec<KeyCode>)>,
+    /// Runs of steps in which the keys turned with gravity or not, see [`ScreenDown::rotate`].
+    /// Older replays have none, their keys never turned.
+    #[serde(default)]
+    pub rotate_camera: Vec<(u32, bool)>,
+}
+
+impl Replay {
+    pub fn new(level: &str) -> Replay {
+        Replay {
+            level: level.to_string(),
+            frames: vec![],
+            rotate_camera: vec![],
+        }
+    }
+
+    pub fn push(&mut self, keys: Vec<KeyCode>, rotate_camera: bool) {
+        push_run(&mut self.frames, keys);
+        push_run(&mut self.rotate_camera, rotate_camera);
+    }
+
+    /// Number of steps.
+    pub fn len(&self) -> usize {
+        self.frames.iter().map(|(count, _)| *count as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn load(path: &Path) -> Result<Replay, String> {
+        let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+        ron::de::from_bytes(&bytes).map_err(|e| e.to_string())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let data = ron::ser::to_string(self).map_err(|e| e.to_string())?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(path, data).map_err(|e| e.to_string())
+    }
+
+    /// `replays/<name>.ron` in the save directory.
+    pub fn path(save_dir: &SaveDir, name: &str) -> Option<PathBuf> {
+        save_dir.path(Path::new(REPLAY_DIR).join(format!("{}.ron", name)))
+    }
+}
+
+fn push_run<T: PartialEq>(runs: &mut Vec<(u32, T)>, value: T) {
+    match runs.last_mut() {
+        Some((count, last)) if *last == value => *count += 1,
+        _ => runs.push((1, value)),
+    }
+}
+
+/// Feeds a [`Replay`] into `Input<KeyCode>` one step at a time.
+pub struct Playback {
+    replay: Replay,
+    run: usize,
+    frame: u32,
+    step: u32,
+    input: Input<KeyCode>,
+}
+
+impl Playback {
+    pub fn new(replay: Replay) -> Playback {
+        Playback {
+            replay,
+            run: 0,
+            frame: 0,
+            step: 0,
+            input: default(),
+        }
+    }
+
+    fn keys(&self) -> Option<&[KeyCode]> {
+        self.replay.frames.get(self.run).map(|(_, keys)| &keys[..])
+    }
+
+    fn rotate_camera(&self) -> bool {
+        let mut end = 0;
+        for (count, rotate) in &self.replay.rotate_camera {
+            end += count;
+            if self.step < end {
+                return *rotate;
+            }
+        }
+        false
+    }
+
+    fn is_started(&self) -> bool {
+        self.run > 0 || self.frame > 0
+    }
+
+    fn advance(&mut self) {
+        self.step += 1;
+        self.frame += 1;
+        if matches!(self.replay.frames.get(self.run), Some((count, _)) if self.frame >= *count) {
+            self.run += 1;
+            self.frame = 0;
+        }
+    }
+}
+
+/// The run being recorded, and the replay being played instead of reading the keyboard.
+#[derive(Default)]
+pub struct Replays {
+    pub recording: Replay,
+    pub playback: Option<Playback>,
+}
+
+impl Replays {
+    /// Starts playing from the next time the level is entered.
+    pub fn play(&mut self, replay: Replay) {
+        self.playback = Some(Playback::new(replay));
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    /// Stops playing, releasing the keys the replay was holding.
+    pub fn stop(&mut self, keys: &mut Input<KeyCode>) {
+        if let Some(playback) = self.playback.take() {
+            for key in playback.input.get_pressed() {
+                keys.release(*key);
+            }
+        }
+    }
+}
+
+pub(super) fn start_recording(mut replays: ResMut<Replays>, current_level: Res<CurrentLevel>) {
+    replays.recording = Replay::new(&current_level.id);
+
+    // The level was restarted in the middle of the replay, which the recording doesn't cover
+    if matches!(&replays.playback, Some(playback) if playback.is_started()) {
+        replays.playback = None;
+    }
+}
+
+/// Checked every frame, a step may not run in the frame the key is pressed.
+pub(super) fn stop_playback(mut replays: ResMut<Replays>, mut keys: ResMut<Input<KeyCode>>) {
+    if replays.is_playing() && keys.just_pressed(WATCH_REPLAY_KEY) {
+        // Pressing the key to stop shouldn't start watching again
+        keys.clear_just_pressed(WATCH_REPLAY_KEY);
+        replays.stop(&mut keys);
+    }
+}
+
+/// Steps before the players are spawned depend on how long the level takes to load, they are
+/// neither recorded nor played.
+pub(super) fn play(
+    mut replays: ResMut<Replays>,
+    mut keys: ResMut<Input<KeyCode>>,
+    mut screen_down: ResMut<ScreenDown>,
+    players: Query<(), With<Player>>,
+) {
+    if players.is_empty() {
+        return;
+    }
+
+    let playback = match &mut replays.playback {
+        Some(playback) => playback,
+        None => return,
+    };
+
+    let frame = match playback.keys() {
+        Some(frame) => frame.to_vec(),
+        None => {
+            replays.stop(&mut keys);
+            return;
+        }
+    };
+
+    let input = &mut playback.input;
+    input.clear();
+    let released: Vec<_> = input
+        .get_pressed()
+        .filter(|key| !frame.contains(key))
+        .copied()
+        .collect();
+    for key in released {
+        input.release(key);
+    }
+    for key in frame {
+        input.press(key);
+    }
+
+    *keys = input.clone();
+    screen_down.rotate = playback.rotate_camera();
+}
+
+pub(super) fn record(
+    mut replays: ResMut<Replays>,
+    keys: Res<Input<KeyCode>>,
+    screen_down: Res<ScreenDown>,
+    players: Query<(), With<Player>>,
+) {
+    if players.is_empty() {
+        return;
+    }
+
+    // Turning the camera is recorded by what it does
+    let mut pressed: Vec<_> = keys
+        .get_pressed()
+        .filter(|key| ![SAVE_REPLAY_KEY, WATCH_REPLAY_KEY, ROTATE_CAMERA_KEY].contains(key))
+        .copied()
+        .collect();
+    pressed.sort();
+    replays.recording.push(pressed, screen_down.rotate);
+
+    if let Some(playback) = &mut replays.playback {
+        playback.advance();
+    }
+}
+
+/// Keeps the run as the level's solution, unless it is a replay itself.
+pub(super) fn save_solution(replays: Res<Replays>, save_dir: Res<SaveDir>) {
+    if !replays.is_playing() {
+        save(&replays.recording, &save_dir, &replays.recording.level);
+    }
+}
+
+pub(super) fn replay_keys(
+    mut replays: ResMut<Replays>,
+    mut level_state: ResMut<State<LevelState>>,
+    current_level: Res<CurrentLevel>,
+    save_dir: Res<SaveDir>,
+    keys: Res<Input<KeyCode>>,
+) {
+    if keys.just_pressed(SAVE_REPLAY_KEY) {
+        let seconds = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        save(
+            &replays.recording,
+            &save_dir,
+            &format!("{}-{}", current_level.id, seconds),
+        );
+    }
+
+    if keys.just_pressed(WATCH_REPLAY_KEY) && !replays.is_playing() {
+        let path = match Replay::path(&save_dir, &current_level.id) {
+            Some(path) => path,
+            None => return,
+        };
+
+        match Replay::load(&path) {
+            Ok(replay) => {
+                replays.play(replay);
+                level_state.restart().unwrap();
+            }
+            Err(error) => warn!("Can't play {}: {}", path.display(), error),
+        }
+    }
+}
+
+fn save(replay: &Replay, save_dir: &SaveDir, name: &str) {
+    if let Some(path) = Replay::path(save_dir, name) {
+        match replay.save(&path) {
+            Ok(()) => info!("Saved replay to {}", path.display()),
+            Err(error) => warn!("Can't save {}: {}", path.display(), error),
+        }
+    }
+}
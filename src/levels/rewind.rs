@@ -0,0 +1,351 @@
+use std::collections::{HashMap, VecDeque};
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::core::{
+    collision_groups, Circuit, Combobox, ComboboxBundle, ComboboxState, Door, DoorButton, Elevator,
+    ElevatorType, GravityBoots, Material, Player, PlayerRectState, FIXED_DT,
+};
+use crate::levels::PendingLevel;
+
+/// Held to go back in time.
+pub const REWIND_KEY: KeyCode = KeyCode::Back;
+
+/// Seconds between snapshots.
+const SNAPSHOT_INTERVAL: f32 = 0.1;
+
+/// Five minutes of history.
+const MAX_SNAPSHOTS: usize = 3000;
+
+/// How much faster than real time the level is rewound.
+const REWIND_SPEED: f32 = 3.0;
+
+/// Recent snapshots of the level, the newest at the back.
+#[derive(Default)]
+pub struct Rewind {
+    snapshots: VecDeque<Snapshot>,
+    timer: f32,
+    rewinding: bool,
+}
+
+impl Rewind {
+    pub fn is_rewinding(&self) -> bool {
+        self.rewinding
+    }
+
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+        self.timer = 0.0;
+    }
+}
+
+struct Snapshot {
+    gravity: Vec2,
+    players: Vec<PlayerSnapshot>,
+    boxes: Vec<BoxSnapshot>,
+    doors: Vec<(Entity, f32)>,
+    buttons: Vec<ButtonSnapshot>,
+    elevators: Vec<(Entity, ElevatorType)>,
+    circuit: Circuit,
+}
+
+struct PlayerSnapshot {
+    entity: Entity,
+    transform: Transform,
+    velocity: Velocity,
+    boots: Option<GravityBoots>,
+    /// Shape and facing, see [`PlayerRectState`].
+    rect_state: (u32, u32),
+}
+
+/// Latches, toggles and countdowns have to match the restored circuit.
+struct ButtonSnapshot {
+    entity: Entity,
+    pressed: bool,
+    enabled: bool,
+    timer: f32,
+}
+
+struct BoxSnapshot {
+    entity: Entity,
+    parent: Entity,
+    combobox: Combobox,
+    state: ComboboxState,
+    transform: Transform,
+    velocity: Velocity,
+}
+
+type PlayerQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Transform,
+        &'static mut Velocity,
+        Option<&'static GravityBoots>,
+        &'static mut PlayerRectState,
+        Option<&'static ImpulseJoint>,
+    ),
+    With<Player>,
+>;
+
+type BoxQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Parent,
+        &'static Combobox,
+        &'static mut ComboboxState,
+        &'static mut Transform,
+        &'static mut Velocity,
+    ),
+    Without<Player>,
+>;
+
+/// Records snapshots, or restores them while [`REWIND_KEY`] is held. Physics is paused while
+/// rewinding, so the level stays exactly as it was.
+pub(super) fn rewind(
+    mut commands: Commands,
+    mut rewind: ResMut<Rewind>,
+    mut players: PlayerQuery,
+    mut boxes: BoxQuery,
+    mut doors: Query<(Entity, &mut Door)>,
+    mut buttons: Query<(Entity, &mut DoorButton)>,
+    mut elevators: Query<(Entity, &mut Elevator)>,
+    pending: Query<(), With<PendingLevel>>,
+    mut config: ResMut<RapierConfiguration>,
+    mut circuit: ResMut<Circuit>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<Material>>,
+    mut assets: ResMut<AssetServer>,
+    keys: Res<Input<KeyCode>>,
+) {
+    // Restarted or reloaded, none of the recorded entities exist anymore
+    if !pending.is_empty() {
+        rewind.clear();
+        return;
+    }
+
+    if !keys.pressed(REWIND_KEY) {
+        if rewind.rewinding {
+            rewind.rewinding = false;
+            rewind.timer = 0.0;
+            config.physics_pipeline_active = true;
+        }
+
+        rewind.timer += FIXED_DT;
+        if rewind.timer >= SNAPSHOT_INTERVAL || rewind.snapshots.is_empty() {
+            rewind.timer = 0.0;
+            let snapshot = take_snapshot(
+                &config, &circuit, &players, &boxes, &doors, &buttons, &elevators,
+            );
+            rewind.snapshots.push_back(snapshot);
+            if rewind.snapshots.len() > MAX_SNAPSHOTS {
+                rewind.snapshots.pop_front();
+            }
+        }
+        return;
+    }
+
+    rewind.rewinding = true;
+    config.physics_pipeline_active = false;
+
+    // Grabbed boxes may not exist in the past
+    for (entity, .., joint) in players.iter() {
+        if joint.is_some() {
+            commands.entity(entity).remove::<ImpulseJoint>();
+        }
+    }
+
+    // The oldest snapshot is kept, holding the key longer stays there
+    rewind.timer += FIXED_DT * REWIND_SPEED;
+    while rewind.timer >= SNAPSHOT_INTERVAL && rewind.snapshots.len() > 1 {
+        rewind.timer -= SNAPSHOT_INTERVAL;
+        rewind.snapshots.pop_back();
+    }
+
+    let snapshot = match rewind.snapshots.back() {
+        Some(snapshot) => snapshot,
+        None => return,
+    };
+
+    config.gravity = snapshot.gravity;
+    *circuit = snapshot.circuit.clone();
+
+    for p in snapshot.players.iter() {
+        if let Ok((_, mut transform, mut velocity, boots, mut rect_state, _)) =
+            players.get_mut(p.entity)
+        {
+            *transform = p.transform;
+            *velocity = p.velocity;
+            if boots != p.boots.as_ref() {
+                match p.boots {
+                    Some(boots) => commands.entity(p.entity).insert(boots),
+                    None => commands.entity(p.entity).remove::<GravityBoots>(),
+                };
+            }
+            let (state, rotation) = p.rect_state;
+            if (rect_state.current_state, rect_state.current_rotation) != p.rect_state {
+                rect_state.current_state = state;
+                rect_state.current_rotation = rotation;
+                commands
+                    .entity(p.entity)
+                    .insert_bundle(rect_state.get_current_bundle());
+            }
+        }
+    }
+
+    for (entity, progress) in snapshot.doors.iter() {
+        if let Ok((_, mut door)) = doors.get_mut(*entity) {
+            door.progress = *progress;
+        }
+    }
+
+    for b in snapshot.buttons.iter() {
+        if let Ok((_, mut button)) = buttons.get_mut(b.entity) {
+            if button.enabled != b.enabled {
+                commands.entity(b.entity).insert(if b.enabled {
+                    button.button_on.clone()
+                } else {
+                    button.button_off.clone()
+                });
+            }
+            button.pressed = b.pressed;
+            button.enabled = b.enabled;
+            button.timer = b.timer;
+        }
+    }
+
+    for (entity, elevator_type) in snapshot.elevators.iter() {
+        if let Ok((_, mut elevator)) = elevators.get_mut(*entity) {
+            elevator.elevator_type = elevator_type.clone();
+        }
+    }
+
+    // Boxes created since the snapshot
+    for (entity, ..) in boxes.iter() {
+        if !snapshot.boxes.iter().any(|b| b.entity == entity) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    // Boxes that were merged away since the snapshot are spawned again
+    let mut respawned = HashMap::new();
+
+    for b in snapshot.boxes.iter() {
+        if let Ok((entity, _, _, mut state, mut transform, mut velocity)) = boxes.get_mut(b.entity)
+        {
+            if body(&state) != body(&b.state) {
+                commands.entity(entity).insert_bundle(body(&b.state));
+            }
+            *state = b.state.clone();
+            *transform = b.transform;
+            *velocity = b.velocity;
+        } else {
+            let mut bundle = ComboboxBundle::new(
+                b.combobox.clone(),
+                b.transform.translation.truncate(),
+                &mut meshes,
+                &mut materials,
+                &mut assets,
+            );
+            bundle.mesh_bundle.transform = b.transform;
+            bundle.combobox_state = b.state.clone();
+            bundle.velocity = b.velocity;
+            (bundle.rigid_body, bundle.collision_groups) = body(&b.state);
+
+            let entity = commands.spawn_bundle(bundle).id();
+            commands.entity(b.parent).add_child(entity);
+            respawned.insert(b.entity, entity);
+        }
+    }
+
+    if !respawned.is_empty() {
+        for snapshot in rewind.snapshots.iter_mut() {
+            for b in snapshot.boxes.iter_mut() {
+                if let Some(entity) = respawned.get(&b.entity) {
+                    b.entity = *entity;
+                }
+            }
+        }
+    }
+}
+
+/// Physics can't be paused when leaving the level in the middle of rewinding.
+pub(super) fn stop_rewind(mut rewind: ResMut<Rewind>, mut config: ResMut<RapierConfiguration>) {
+    rewind.rewinding = false;
+    rewind.clear();
+    config.physics_pipeline_active = true;
+}
+
+fn take_snapshot(
+    config: &RapierConfiguration,
+    circuit: &Circuit,
+    players: &PlayerQuery,
+    boxes: &BoxQuery,
+    doors: &Query<(Entity, &mut Door)>,
+    buttons: &Query<(Entity, &mut DoorButton)>,
+    elevators: &Query<(Entity, &mut Elevator)>,
+) -> Snapshot {
+    Snapshot {
+        gravity: config.gravity,
+        players: players
+            .iter()
+            .map(
+                |(entity, transform, velocity, boots, rect_state, _)| PlayerSnapshot {
+                    entity,
+                    transform: *transform,
+                    velocity: *velocity,
+                    boots: boots.copied(),
+                    rect_state: (rect_state.current_state, rect_state.current_rotation),
+                },
+            )
+            .collect(),
+        boxes: boxes
+            .iter()
+            .filter(|(.., state, _, _)| **state != ComboboxState::Despawned)
+            .map(
+                |(entity, parent, combobox, state, transform, velocity)| BoxSnapshot {
+                    entity,
+                    parent: parent.get(),
+                    combobox: combobox.clone(),
+                    state: state.clone(),
+                    transform: *transform,
+                    velocity: *velocity,
+                },
+            )
+            .collect(),
+        doors: doors
+            .iter()
+            .map(|(entity, door)| (entity, door.progress))
+            .collect(),
+        buttons: buttons
+            .iter()
+            .map(|(entity, button)| ButtonSnapshot {
+                entity,
+                pressed: button.pressed,
+                enabled: button.enabled,
+                timer: button.timer,
+            })
+            .collect(),
+        elevators: elevators
+            .iter()
+            .map(|(entity, elevator)| (entity, elevator.elevator_type.clone()))
+            .collect(),
+        circuit: circuit.clone(),
+    }
+}
+
+/// Same bodies as the spawning and despawning animations use.
+fn body(state: &ComboboxState) -> (RigidBody, CollisionGroups) {
+    if *state == ComboboxState::Normal {
+        (RigidBody::Dynamic, collision_groups::COMBOBOX)
+    } else {
+        (
+            RigidBody::KinematicPositionBased,
+            CollisionGroups::new(0, 0),
+        )
+    }
+}
@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::core::{
+    BodyGravity, ComboboxMerged, ComboboxUndone, Player, PlayerJumped, SceneBuilder, FIXED_DT,
+};
+use crate::levels::{CurrentLevel, Rewind};
+use crate::states::GuiState;
+
+/// What happened during one attempt at a level, restarts included.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LevelStats {
+    /// Seconds, not counting the level completed screen.
+    pub time: f32,
+    pub restarts: u32,
+    /// Merges with an undo box are counted as undos only.
+    pub merges: u32,
+    pub undos: u32,
+    pub jumps: u32,
+    /// Level cells walked by all players together.
+    pub distance: f32,
+}
+
+/// The attempt in progress. It lasts until the level is completed or left, so restarting
+/// the level continues it.
+#[derive(Debug, Default)]
+pub struct LevelAttempt {
+    /// `None` once the attempt is over, the next time the level is set up starts a new one.
+    pub level: Option<String>,
+    pub stats: LevelStats,
+}
+
+impl LevelAttempt {
+    pub fn end(&mut self) {
+        self.level = None;
+    }
+}
+
+pub(super) fn start_attempt(mut attempt: ResMut<LevelAttempt>, current_level: Res<CurrentLevel>) {
+    if attempt.level.as_ref() != Some(&current_level.id) {
+        *attempt = LevelAttempt {
+            level: Some(current_level.id.clone()),
+            stats: default(),
+        };
+    }
+}
+
+pub(super) fn end_attempt(mut attempt: ResMut<LevelAttempt>) {
+    attempt.end();
+}
+
+pub(super) fn count_time(
+    mut attempt: ResMut<LevelAttempt>,
+    gui_state: Res<State<GuiState>>,
+    rewind: Res<Rewind>,
+) {
+    // Going back in time doesn't take any
+    if *gui_state.current() == GuiState::Level && !rewind.is_rewinding() {
+        attempt.stats.time += FIXED_DT;
+    }
+}
+
+/// Only movement across gravity is counted, falling isn't walking.
+pub(super) fn count_distance(
+    mut attempt: ResMut<LevelAttempt>,
+    players: Query<(&Velocity, &BodyGravity), With<Player>>,
+    gui_state: Res<State<GuiState>>,
+) {
+    if *gui_state.current() != GuiState::Level {
+        return;
+    }
+
+    for (velocity, gravity) in players.iter() {
+        let right = gravity.direction().get_perp().get_vec();
+        attempt.stats.distance +=
+            velocity.linvel.dot(right).abs() * FIXED_DT / SceneBuilder::CELL_SIZE;
+    }
+}
+
+pub(super) fn count_merges(
+    mut attempt: ResMut<LevelAttempt>,
+    mut merged: EventReader<ComboboxMerged>,
+    mut undone: EventReader<ComboboxUndone>,
+) {
+    attempt.stats.merges += merged.iter().count() as u32;
+    attempt.stats.undos += undone.iter().count() as u32;
+}
+
+pub(super) fn count_jumps(
+    mut attempt: ResMut<LevelAttempt>,
+    mut jumped: EventReader<PlayerJumped>,
+) {
+    attempt.stats.jumps += jumped.iter().count() as u32;
+}
@@ -0,0 +1,215 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use bevy::prelude::*;
+use bevy::sprite::Rect;
+
+use crate::core::{ButtonMode, ComboboxType, ElevatorType, PlayerIndex, SceneBuilder};
+use crate::levels::{LevelDescription, LevelObject};
+
+/// Players further than this from the origin restart the level when it has no boundaries,
+/// see `restart_on_out_of_boundaries`.
+const MAX_DISTANCE: f32 = 10000.0;
+
+/// Looks for mistakes that make a level misbehave without failing to load. `assets` is used to
+/// check that referenced images exist.
+pub fn validate(description: &LevelDescription, assets: &Path) -> Vec<String> {
+    let mut errors = vec![];
+
+    let walls: Vec<Rect> = description
+        .objects
+        .iter()
+        .filter(|object| matches!(object, LevelObject::Wall { .. }))
+        .map(|object| object.bounds())
+        .collect();
+
+    let signals: HashSet<&str> = description
+        .objects
+        .iter()
+        .filter_map(|object| match object {
+            LevelObject::Button { signal, .. }
+            | LevelObject::PressurePlate { signal, .. }
+            | LevelObject::Sensor { signal, .. } => Some(signal.as_str()),
+            _ => None,
+        })
+        .chain(description.logic.iter().map(|gate| gate.output.as_str()))
+        .collect();
+
+    for gate in description.logic.iter() {
+        for input in gate.kind.inputs() {
+            if !signals.contains(input) {
+                errors.push(format!(
+                    "gate for signal {:?} reads signal {:?} which nothing turns on",
+                    gate.output, input
+                ));
+            }
+        }
+    }
+
+    for object in description.objects.iter() {
+        let (name, signal) = match object {
+            LevelObject::Door { signal, .. } => ("door", Some(signal)),
+            LevelObject::Dispenser { signal, .. } => ("dispenser", Some(signal)),
+            LevelObject::Light { signal, .. } => ("light", signal.as_ref()),
+            LevelObject::Elevator { signal, .. } => ("elevator", signal.as_ref()),
+            LevelObject::FinishPoint { signal, .. } => ("finish point", signal.as_ref()),
+            LevelObject::Button {
+                mode: ButtonMode::Latch { reset },
+                ..
+            } => ("latching button", reset.as_ref()),
+            _ => continue,
+        };
+
+        if let Some(signal) = signal.filter(|signal| !signals.contains(signal.as_str())) {
+            let bounds = object.bounds();
+            let center = (bounds.min + bounds.max) * 0.5;
+            errors.push(format!(
+                "{} at ({}, {}) listens to signal {:?} which nothing turns on",
+                name, center.x, center.y, signal
+            ));
+        }
+    }
+
+    let mut two_players = [false; 2];
+
+    for object in description.objects.iter() {
+        match object {
+            LevelObject::SpawnPoint { x, y, index } => match index {
+                PlayerIndex::SinglePlayer => {}
+                PlayerIndex::TwoPlayers(i) if *i < two_players.len() => two_players[*i] = true,
+                PlayerIndex::TwoPlayers(i) => errors.push(format!(
+                    "spawn point at ({}, {}) is for player {}, only two players are supported",
+                    x, y, i
+                )),
+            },
+            LevelObject::FinishPoint { x, y, .. } => {
+                let position = Vec2::new(*x, *y);
+                let outside = match description.boundaries {
+                    Some(b) => {
+                        position.x < b.left
+                            || position.x > b.right
+                            || position.y < b.bottom
+                            || position.y > b.top
+                    }
+                    None => position.length() * SceneBuilder::CELL_SIZE > MAX_DISTANCE,
+                };
+
+                if outside {
+                    errors.push(format!(
+                        "finish point at ({}, {}) is outside of the level boundaries",
+                        x, y
+                    ));
+                }
+            }
+            LevelObject::Box { x, y, combobox } => {
+                let bounds = object.bounds();
+                if walls.iter().any(|wall| overlap(bounds, *wall)) {
+                    errors.push(format!("box at ({}, {}) is inside a wall", x, y));
+                }
+
+                let exists = || {
+                    combobox
+                        .box_type
+                        .overlay()
+                        .is_some_and(|path| assets.join(path).exists())
+                };
+
+                match combobox.box_type {
+                    ComboboxType::Buff(buff) if !exists() => errors.push(format!(
+                        "box at ({}, {}) has buff {} which has no overlay image",
+                        x, y, buff
+                    )),
+                    ComboboxType::Split(pieces) if !exists() => errors.push(format!(
+                        "box at ({}, {}) splits into {} pieces which has no overlay image",
+                        x, y, pieces
+                    )),
+                    _ => {}
+                }
+            }
+            LevelObject::PressurePlate {
+                x,
+                y,
+                min_weight,
+                max_weight: Some(max_weight),
+                ..
+            } if max_weight < min_weight => {
+                errors.push(format!(
+                    "pressure plate at ({}, {}) needs at least {} but at most {} weight",
+                    x, y, min_weight, max_weight
+                ));
+            }
+            LevelObject::Elevator {
+                start,
+                elevator_type,
+                signal,
+                ..
+            } => {
+                let speeds_ok = match elevator_type {
+                    ElevatorType::Loop {
+                        period: seconds, ..
+                    }
+                    | ElevatorType::Triggered { seconds, .. }
+                    | ElevatorType::OneShot { seconds, .. } => *seconds > 0.0,
+                    ElevatorType::Path {
+                        waypoints, speed, ..
+                    } => *speed > 0.0 && waypoints.iter().all(|waypoint| waypoint.speed > 0.0),
+                };
+                if !speeds_ok {
+                    errors.push(format!(
+                        "elevator at ({}, {}) never gets anywhere",
+                        start.x, start.y
+                    ));
+                }
+
+                if matches!(elevator_type, ElevatorType::Triggered { .. }) && signal.is_none() {
+                    errors.push(format!(
+                        "elevator at ({}, {}) waits for a signal but has none",
+                        start.x, start.y
+                    ));
+                }
+            }
+            LevelObject::Button {
+                x,
+                y,
+                mode: ButtonMode::Timed { seconds },
+                ..
+            } if *seconds <= 0.0 => {
+                errors.push(format!(
+                    "timed button at ({}, {}) stays on for {} seconds",
+                    x, y, seconds
+                ));
+            }
+            LevelObject::GravityZone {
+                left,
+                bottom,
+                strength,
+                ..
+            } if *strength < 0.0 => {
+                errors.push(format!(
+                    "gravity zone at ({}, {}) has negative strength {}, turn its direction instead",
+                    left, bottom, strength
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    if two_players[0] != two_players[1] {
+        let (present, missing) = if two_players[0] { (0, 1) } else { (1, 0) };
+        errors.push(format!(
+            "there is a spawn point for player {} of two, but not for player {}",
+            present, missing
+        ));
+    }
+
+    errors
+}
+
+/// Whether the rectangles overlap by more than just touching.
+fn overlap(a: Rect, b: Rect) -> bool {
+    const EPSILON: f32 = 0.01;
+    a.min.x + EPSILON < b.max.x
+        && b.min.x + EPSILON < a.max.x
+        && a.min.y + EPSILON < b.max.y
+        && b.min.y + EPSILON < a.max.y
+}
@@ -0,0 +1,11 @@
+#![allow(dead_code)]
+// todo: do warn(dead_code) sometimes, too noisy during development
+
+pub mod core;
+pub mod editor;
+pub mod game;
+pub mod gui;
+pub mod levels;
+pub mod solver;
+pub mod states;
+pub mod utils;
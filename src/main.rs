@@ -1,19 +1,11 @@
-#![allow(dead_code)]
-// todo: do warn(dead_code) sometimes, too noisy during development
-
 use bevy::{asset::AssetServerSettings, prelude::*};
 use bevy_kira_audio::AudioPlugin;
-
-use crate::game::ComboboxGamePlugin;
 #[cfg(debug_assertions)]
-use crate::utils::FpsPlugin;
+use bevy_rapier2d::render::RapierDebugRenderPlugin;
 
-mod core;
-mod game;
-mod gui;
-mod levels;
-mod states;
-mod utils;
+use combobox_game::game::ComboboxGamePlugin;
+#[cfg(debug_assertions)]
+use combobox_game::utils::FpsPlugin;
 
 fn main() {
     let mut app = App::new();
@@ -37,6 +29,10 @@ fn main() {
     #[cfg(debug_assertions)]
     app.add_plugin(FpsPlugin);
 
+    // Draw colliders
+    #[cfg(debug_assertions)]
+    app.add_plugin(RapierDebugRenderPlugin::default());
+
     // Our incredible game plugin
     app.add_plugin(ComboboxGamePlugin);
 
@@ -0,0 +1,208 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy::sprite::Rect;
+
+use crate::utils::SceneDirection;
+
+/// Size of a grid cell, in level cells.
+pub const RESOLUTION: f32 = 0.5;
+
+/// Player footprint in grid cells, across and along gravity.
+const PLAYER_WIDTH: i32 = 2;
+const PLAYER_HEIGHT: i32 = 4;
+
+/// Rasterized level, solid where walls and closed doors are.
+pub struct Grid {
+    min: IVec2,
+    size: IVec2,
+    walls: Vec<bool>,
+    doors: Vec<Vec<usize>>,
+    ladders: Vec<bool>,
+}
+
+/// Cells the player can touch.
+pub struct Area {
+    min: IVec2,
+    size: IVec2,
+    cells: Vec<bool>,
+}
+
+impl Grid {
+    /// `extent` is the part of the level that matters, everything outside of it is solid.
+    pub fn new(extent: Rect, walls: &[Rect], doors: &[Rect], ladders: &[Rect]) -> Grid {
+        let min = (extent.min / RESOLUTION).floor().as_ivec2();
+        let max = (extent.max / RESOLUTION).ceil().as_ivec2();
+        let mut grid = Grid {
+            min,
+            size: max - min,
+            walls: vec![],
+            doors: vec![],
+            ladders: vec![],
+        };
+
+        grid.walls = grid.rasterize(walls);
+        grid.ladders = grid.rasterize(ladders);
+        grid.doors = doors
+            .iter()
+            .map(|door| {
+                grid.rasterize(std::slice::from_ref(door))
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(_, solid)| *solid)
+                    .map(|(i, _)| i)
+                    .collect()
+            })
+            .collect();
+
+        grid
+    }
+
+    fn rasterize(&self, rects: &[Rect]) -> Vec<bool> {
+        (0..self.size.x * self.size.y)
+            .map(|i| {
+                let center = self.center(IVec2::new(i % self.size.x, i / self.size.x) + self.min);
+                rects
+                    .iter()
+                    .any(|rect| center.cmpgt(rect.min).all() && center.cmplt(rect.max).all())
+            })
+            .collect()
+    }
+
+    fn center(&self, cell: IVec2) -> Vec2 {
+        (cell.as_vec2() + Vec2::splat(0.5)) * RESOLUTION
+    }
+
+    fn index(&self, cell: IVec2) -> Option<usize> {
+        let local = cell - self.min;
+        if local.cmplt(IVec2::ZERO).any() || local.cmpge(self.size).any() {
+            return None;
+        }
+        Some((local.y * self.size.x + local.x) as usize)
+    }
+
+    /// Cells the player can touch when starting from `spawns` and able to climb up to
+    /// `climb` grid cells at once.
+    pub fn reachable(
+        &self,
+        spawns: &[Vec2],
+        gravity: SceneDirection,
+        climb: i32,
+        open_doors: u64,
+    ) -> Area {
+        let mut solid = self.walls.clone();
+        for (i, door) in self.doors.iter().enumerate() {
+            if open_doors & (1 << i) == 0 {
+                for &cell in door {
+                    solid[cell] = true;
+                }
+            }
+        }
+
+        let up = (-gravity.get_vec()).as_ivec2();
+        let side = gravity.get_perp().get_vec().as_ivec2();
+        let footprint = |anchor: IVec2| {
+            (0..PLAYER_WIDTH)
+                .flat_map(move |i| (0..PLAYER_HEIGHT).map(move |j| anchor + side * i + up * j))
+        };
+        let fits = |anchor: IVec2| {
+            footprint(anchor).all(|cell| matches!(self.index(cell), Some(i) if !solid[i]))
+        };
+        let supported = |anchor: IVec2| {
+            (0..PLAYER_WIDTH)
+                .any(|i| matches!(self.index(anchor + side * i - up), Some(i) if solid[i]))
+                || footprint(anchor)
+                    .any(|cell| matches!(self.index(cell), Some(i) if self.ladders[i]))
+        };
+
+        // Highest remaining climb each anchor was visited with
+        let mut best = vec![-1; solid.len()];
+        let mut queue = VecDeque::new();
+
+        for spawn in spawns {
+            let cell = (*spawn / RESOLUTION).floor().as_ivec2();
+            let anchor = cell - side * (PLAYER_WIDTH / 2) - up * (PLAYER_HEIGHT / 2);
+
+            // Spawn points are placed by hand, so nudge the player out of nearby walls
+            let start = (0..PLAYER_HEIGHT)
+                .flat_map(|j| {
+                    [
+                        anchor + up * j,
+                        anchor - side + up * j,
+                        anchor + side + up * j,
+                    ]
+                })
+                .find(|anchor| fits(*anchor));
+
+            if let Some(start) = start {
+                queue.push_back((start, climb));
+            }
+        }
+
+        while let Some((anchor, mut remaining)) = queue.pop_front() {
+            let index = match self.index(anchor) {
+                Some(index) => index,
+                None => continue,
+            };
+            if supported(anchor) {
+                remaining = climb;
+            }
+            if best[index] >= remaining {
+                continue;
+            }
+            best[index] = remaining;
+
+            let mut moves = vec![
+                (anchor + side, remaining),
+                (anchor - side, remaining),
+                (anchor - up, 0),
+            ];
+            if remaining > 0 {
+                moves.push((anchor + up, remaining - 1));
+            }
+
+            for (next, remaining) in moves {
+                if fits(next) && matches!(self.index(next), Some(i) if best[i] < remaining) {
+                    queue.push_back((next, remaining));
+                }
+            }
+        }
+
+        let mut cells = vec![false; solid.len()];
+        for (index, remaining) in best.iter().enumerate() {
+            if *remaining >= 0 {
+                let anchor =
+                    IVec2::new(index as i32 % self.size.x, index as i32 / self.size.x) + self.min;
+                for cell in footprint(anchor) {
+                    if let Some(i) = self.index(cell) {
+                        cells[i] = true;
+                    }
+                }
+            }
+        }
+
+        Area {
+            min: self.min,
+            size: self.size,
+            cells,
+        }
+    }
+}
+
+impl Area {
+    /// Whether the player can get within `margin` level cells of `rect`.
+    pub fn touches(&self, rect: Rect, margin: f32) -> bool {
+        let min = ((rect.min - Vec2::splat(margin)) / RESOLUTION)
+            .floor()
+            .as_ivec2()
+            - self.min;
+        let max = ((rect.max + Vec2::splat(margin)) / RESOLUTION)
+            .ceil()
+            .as_ivec2()
+            - self.min;
+        let min = min.max(IVec2::ZERO);
+        let max = max.min(self.size);
+
+        (min.y..max.y).any(|y| (min.x..max.x).any(|x| self.cells[(y * self.size.x + x) as usize]))
+    }
+}
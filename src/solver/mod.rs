@@ -0,0 +1,533 @@
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::fmt;
+
+use bevy::prelude::*;
+use bevy::sprite::Rect;
+
+use crate::core::{
+    Combobox, ComboboxType, Gate, GateKind, MergeRecipes, PlayerIndex, PressurePlate,
+};
+use crate::levels::{LevelDescription, LevelObject};
+use crate::utils::SceneDirection;
+
+pub use grid::*;
+
+mod grid;
+
+/// Search stops after visiting this many states.
+pub const MAX_STATES: usize = 50_000;
+
+/// How high the player jumps on their own, in grid cells.
+const JUMP_HEIGHT: i32 = 4;
+
+/// Extra climb height after a merge, the new box pushes the player up while it grows.
+const MERGE_LAUNCH: i32 = 6;
+
+/// Climb height used when a box can fly against gravity and lift the player anywhere.
+const UNLIMITED_CLIMB: i32 = 1000;
+
+/// Outcome of [`solve`].
+#[derive(Debug, Clone)]
+pub enum Solution {
+    /// The finish can be reached after these merges, in order.
+    Solvable(Vec<String>),
+    Unsolvable,
+    /// The state space is too big, the level has more boxes or doors than the solver tracks, or
+    /// dispensers that make new boxes.
+    Inconclusive,
+}
+
+impl fmt::Display for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Solution::Solvable(merges) if merges.is_empty() => write!(f, "solvable without merges"),
+            Solution::Solvable(merges) => {
+                write!(f, "solvable in {} merge(s)", merges.len())?;
+                for (i, merge) in merges.iter().enumerate() {
+                    write!(f, "\n  {}. {}", i + 1, merge)?;
+                }
+                Ok(())
+            }
+            Solution::Unsolvable => write!(f, "finish is unreachable"),
+            Solution::Inconclusive => write!(f, "inconclusive"),
+        }
+    }
+}
+
+/// Searches for the shortest sequence of merges after which the player can reach the finish.
+///
+/// The level is reduced to a discrete model: the player walks and jumps on a grid, any box
+/// the player can touch is assumed to be carried along, and a door is open once enough
+/// reachable buttons and sensors can be held down by the player and the carried boxes to turn
+/// its signal on, if they are heavy enough for the pressure plates among them. The model is
+/// optimistic, so an `Unsolvable` level is broken, but a `Solvable` one may still be impossible
+/// to finish in the game.
+///
+/// `recipes` are the ones shared by all levels, see [`crate::levels::read_recipes`].
+pub fn solve(description: &LevelDescription, recipes: &MergeRecipes) -> Solution {
+    let recipes = recipes.with(&description.recipes);
+
+    let level = match Level::new(description) {
+        Some(level) => level,
+        None => return Solution::Inconclusive,
+    };
+
+    let start = level.settle(State {
+        carried: vec![],
+        home: (1u64 << level.boxes.len()) - 1,
+        open_doors: 0,
+        merged: false,
+    });
+
+    // Every state found so far, with the state and merge it was reached from
+    let mut visited: HashMap<_, Option<(usize, String)>> = HashMap::new();
+    visited.insert(start.0.key(), None);
+    let mut states = vec![start];
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(current) = queue.pop_front() {
+        let (state, finished) = states[current].clone();
+
+        if finished {
+            let mut merges = vec![];
+            let mut current = &visited[&state.key()];
+            while let Some((parent, merge)) = current {
+                merges.push(merge.clone());
+                current = &visited[&states[*parent].0.key()];
+            }
+            merges.reverse();
+            return Solution::Solvable(merges);
+        }
+
+        if states.len() > MAX_STATES {
+            return Solution::Inconclusive;
+        }
+
+        for (next, merge) in state.merges(&recipes) {
+            let next = level.settle(next);
+            let key = next.0.key();
+
+            if let Entry::Vacant(entry) = visited.entry(key) {
+                entry.insert(Some((current, merge)));
+                states.push(next);
+                queue.push_back(states.len() - 1);
+            }
+        }
+    }
+
+    Solution::Unsolvable
+}
+
+struct Button {
+    signal: String,
+    bounds: Rect,
+    /// Pressure plates need the weight on them to be in range.
+    plate: Option<PressurePlate>,
+    /// Toggles, latches and timed buttons don't have to be held down, timers are assumed to last
+    /// long enough to get where they are needed.
+    stays_on: bool,
+}
+
+struct Level {
+    grid: Grid,
+    spawns: Vec<Vec2>,
+    players: usize,
+    finish_points: Vec<(Rect, Option<String>)>,
+    boxes: Vec<(Combobox, Rect)>,
+    /// Buttons and sensors.
+    buttons: Vec<Button>,
+    doors: Vec<(String, Rect)>,
+    logic: Vec<Gate>,
+}
+
+impl Level {
+    fn new(description: &LevelDescription) -> Option<Level> {
+        let mut walls = vec![];
+        let mut ladders = vec![];
+        let mut single_spawns = vec![];
+        let mut spawns = vec![];
+        let mut level = Level {
+            grid: Grid::new(Rect::default(), &[], &[], &[]),
+            spawns: vec![],
+            players: 1,
+            finish_points: vec![],
+            boxes: vec![],
+            buttons: vec![],
+            doors: vec![],
+            logic: description.logic.clone(),
+        };
+
+        let mut extent: Option<Rect> = None;
+
+        for object in description.objects.iter() {
+            let bounds = object.bounds();
+
+            match object {
+                LevelObject::Wall { .. } => {
+                    walls.push(bounds);
+                    continue;
+                }
+                // Players falling their own ways don't fit the model
+                LevelObject::Box { combobox, .. } if combobox.box_type == ComboboxType::Boots => {
+                    return None
+                }
+                LevelObject::Box { combobox, .. } => level.boxes.push((combobox.clone(), bounds)),
+                LevelObject::Door { signal, .. } => level.doors.push((signal.clone(), bounds)),
+                LevelObject::Button { signal, mode, .. } => level.buttons.push(Button {
+                    signal: signal.clone(),
+                    bounds,
+                    plate: None,
+                    stays_on: !mode.is_momentary(),
+                }),
+                LevelObject::Sensor { signal, .. } => level.buttons.push(Button {
+                    signal: signal.clone(),
+                    bounds,
+                    plate: None,
+                    stays_on: false,
+                }),
+                LevelObject::PressurePlate {
+                    signal,
+                    min_weight,
+                    max_weight,
+                    ..
+                } => level.buttons.push(Button {
+                    signal: signal.clone(),
+                    bounds,
+                    plate: Some(PressurePlate::new(*min_weight, *max_weight)),
+                    stays_on: false,
+                }),
+                LevelObject::Dispenser { .. } | LevelObject::GravityZone { .. } => return None,
+                LevelObject::Elevator { .. } => ladders.push(bounds),
+                LevelObject::SpawnPoint { x, y, index } => match index {
+                    PlayerIndex::SinglePlayer => single_spawns.push(Vec2::new(*x, *y)),
+                    PlayerIndex::TwoPlayers(_) => spawns.push(Vec2::new(*x, *y)),
+                },
+                LevelObject::FinishPoint { signal, .. } => {
+                    level.finish_points.push((bounds, signal.clone()))
+                }
+                LevelObject::Hint { .. } | LevelObject::Light { .. } => {}
+            }
+
+            extent = Some(match extent {
+                Some(extent) => Rect {
+                    min: extent.min.min(bounds.min),
+                    max: extent.max.max(bounds.max),
+                },
+                None => bounds,
+            });
+        }
+
+        if level.boxes.len() >= 64 || level.doors.len() > 64 {
+            return None;
+        }
+
+        if !single_spawns.is_empty() {
+            spawns = single_spawns;
+        } else {
+            level.players = spawns.len();
+        }
+        level.spawns = spawns;
+
+        let extent = extent?;
+        let margin = Vec2::splat(8.0);
+        let doors: Vec<_> = level.doors.iter().map(|(_, bounds)| *bounds).collect();
+        level.grid = Grid::new(
+            Rect {
+                min: extent.min - margin,
+                max: extent.max + margin,
+            },
+            &walls,
+            &doors,
+            &ladders,
+        );
+
+        Some(level)
+    }
+
+    /// Repeats picking up boxes and opening doors until nothing changes anymore.
+    fn settle(&self, mut state: State) -> (State, bool) {
+        loop {
+            let gravity = self.gravity(&state);
+            let climb = state.climb(gravity);
+            let area = self
+                .grid
+                .reachable(&self.spawns, gravity, climb, state.open_doors);
+
+            let mut changed = false;
+
+            for (i, (combobox, bounds)) in self.boxes.iter().enumerate() {
+                if state.home & (1 << i) != 0 && area.touches(*bounds, RESOLUTION) {
+                    state.home &= !(1 << i);
+                    state.carried.push(combobox.clone());
+                    changed = true;
+                }
+            }
+
+            // Optimistically everything can be stacked on one plate, or the lightest one alone
+            let weights = state
+                .carried
+                .iter()
+                .map(|combobox| combobox.weight)
+                .chain((0..self.players).map(|_| PressurePlate::PLAYER_WEIGHT));
+            let total: f32 = weights.clone().sum();
+            let lightest = weights.fold(f32::MAX, f32::min);
+
+            let buttons: Vec<&Button> = self
+                .buttons
+                .iter()
+                .filter(|button| area.touches(button.bounds, RESOLUTION))
+                .filter(|button| match &button.plate {
+                    Some(plate) => {
+                        total >= plate.min_weight
+                            && plate.max_weight.is_none_or(|max| lightest <= max)
+                    }
+                    None => true,
+                })
+                .collect();
+            let pressers = state.carried.len() + self.players;
+            let signals = self.held_buttons(&buttons, pressers);
+
+            for (i, (signal, _)) in self.doors.iter().enumerate() {
+                if state.open_doors & (1 << i) == 0 && signals.contains_key(signal.as_str()) {
+                    state.open_doors |= 1 << i;
+                    changed = true;
+                }
+            }
+
+            if !changed {
+                let finished = self.finish_points.iter().any(|(bounds, signal)| {
+                    area.touches(*bounds, 0.0)
+                        && signal
+                            .as_deref()
+                            .is_none_or(|signal| signals.contains_key(signal))
+                });
+                state.carried.sort_by_key(box_key);
+                return (state, finished);
+            }
+        }
+    }
+
+    /// The signals `pressers` can turn on with the `buttons` they reach, each with the fewest
+    /// buttons that have to be held down at the same time for it. Buttons and gates that
+    /// remember being pressed only need it once, and a `Not` is assumed to be off when it has
+    /// to be.
+    fn held_buttons<'a>(
+        &'a self,
+        buttons: &[&'a Button],
+        pressers: usize,
+    ) -> HashMap<&'a str, BTreeSet<&'a str>> {
+        let mut held: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+        for button in buttons {
+            let signal = button.signal.as_str();
+            if button.stays_on {
+                held.insert(signal, BTreeSet::new());
+            } else {
+                held.entry(signal)
+                    .or_insert_with(|| BTreeSet::from([signal]));
+            }
+        }
+
+        // Gates may be listed before the ones feeding them, repeat until nothing improves
+        for _ in 0..=self.logic.len() {
+            let mut changed = false;
+
+            for gate in self.logic.iter() {
+                let inputs = gate.kind.inputs();
+                let needed = match &gate.kind {
+                    GateKind::And(_) => inputs
+                        .iter()
+                        .map(|input| held.get(input))
+                        .collect::<Option<Vec<_>>>()
+                        .map(|sets| sets.into_iter().flatten().copied().collect()),
+                    GateKind::Or(_) | GateKind::Xor(_) | GateKind::Delay { .. } => inputs
+                        .iter()
+                        .filter_map(|input| held.get(input))
+                        .min_by_key(|set| set.len())
+                        .cloned(),
+                    GateKind::Not(_) => Some(BTreeSet::new()),
+                    GateKind::Latch { .. }
+                    | GateKind::Toggle(_)
+                    | GateKind::Timer { .. }
+                    | GateKind::Counter { .. } => held.get(inputs[0]).map(|_| BTreeSet::new()),
+                };
+
+                let needed = match needed {
+                    Some(needed) if needed.len() <= pressers => needed,
+                    _ => continue,
+                };
+                if held
+                    .get(gate.output.as_str())
+                    .is_none_or(|current| needed.len() < current.len())
+                {
+                    held.insert(&gate.output, needed);
+                    changed = true;
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        held
+    }
+
+    fn gravity(&self, state: &State) -> SceneDirection {
+        let home = self
+            .boxes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| state.home & (1 << i) != 0)
+            .map(|(_, (combobox, _))| combobox);
+
+        state
+            .carried
+            .iter()
+            .chain(home)
+            .filter(|combobox| combobox.box_type == ComboboxType::Gravity)
+            .find_map(|combobox| combobox.local_gravity)
+            .map(direction_from_vec)
+            .unwrap_or(SceneDirection::Down)
+    }
+}
+
+fn direction_from_vec(direction: Vec2) -> SceneDirection {
+    [
+        SceneDirection::Down,
+        SceneDirection::Right,
+        SceneDirection::Up,
+        SceneDirection::Left,
+    ]
+    .into_iter()
+    .max_by(|a, b| {
+        a.get_vec()
+            .dot(direction)
+            .partial_cmp(&b.get_vec().dot(direction))
+            .unwrap()
+    })
+    .unwrap()
+}
+
+fn box_key(combobox: &Combobox) -> String {
+    format!("{:?}", combobox)
+}
+
+fn describe(combobox: &Combobox) -> String {
+    let mut description = format!("{:?} x{}", combobox.box_type, combobox.weight);
+    if let (ComboboxType::Standard { .. } | ComboboxType::Lamp { .. }, Some(gravity)) =
+        (&combobox.box_type, combobox.local_gravity)
+    {
+        description += &format!(" falling {:?}", direction_from_vec(gravity));
+    }
+    description
+}
+
+#[derive(Debug, Clone)]
+struct State {
+    /// Boxes the player has reached, sorted by [`box_key`].
+    carried: Vec<Combobox>,
+    /// Boxes from the level description that haven't been reached yet.
+    home: u64,
+    open_doors: u64,
+    merged: bool,
+}
+
+impl State {
+    fn key(&self) -> (Vec<String>, u64, u64, bool) {
+        (
+            self.carried.iter().map(box_key).collect(),
+            self.home,
+            self.open_doors,
+            self.merged,
+        )
+    }
+
+    /// Boxes are dragged along the floor, so the player can only use one of them as a step.
+    /// A box falling against gravity lifts the player anywhere.
+    fn climb(&self, gravity: SceneDirection) -> i32 {
+        let flying = self.carried.iter().any(|combobox| {
+            combobox.box_type != ComboboxType::Gravity
+                && matches!(combobox.local_gravity, Some(g) if g.dot(gravity.get_vec()) < 0.5)
+        });
+
+        if flying {
+            return UNLIMITED_CLIMB;
+        }
+
+        let step = self
+            .carried
+            .iter()
+            .map(|combobox| combobox.world_size() / Combobox::DEFAULT_SIZE)
+            .fold(0.0, f32::max);
+
+        let launch = if self.merged { MERGE_LAUNCH } else { 0 };
+
+        JUMP_HEIGHT + (step / RESOLUTION).floor() as i32 + launch
+    }
+
+    fn merges(&self, recipes: &MergeRecipes) -> Vec<(State, String)> {
+        let mut merges = vec![];
+
+        for i in 0..self.carried.len() {
+            for j in i + 1..self.carried.len() {
+                let (first, second) = (&self.carried[i], &self.carried[j]);
+
+                if let Some(result) = Combobox::merge(first, Vec2::ZERO, second, Vec2::X, recipes) {
+                    let mut carried: Vec<_> = self
+                        .carried
+                        .iter()
+                        .enumerate()
+                        .filter(|(k, _)| *k != i && *k != j)
+                        .map(|(_, combobox)| combobox.clone())
+                        .collect();
+
+                    // The other pieces of a split box are undone along with it
+                    let undone = match (&first.box_type, &second.box_type) {
+                        (ComboboxType::Undo, _) => Some(second),
+                        (_, ComboboxType::Undo) => Some(first),
+                        _ => None,
+                    };
+                    if let Some(piece) = undone {
+                        for _ in 1..piece.split_pieces() {
+                            match carried.iter().position(|other| piece.is_piece_like(other)) {
+                                Some(k) => {
+                                    carried.remove(k);
+                                }
+                                None => break,
+                            }
+                        }
+                        if carried.len() + piece.split_pieces() + 1 != self.carried.len() {
+                            continue;
+                        }
+                    }
+
+                    let description = format!(
+                        "{} + {} -> {}",
+                        describe(first),
+                        describe(second),
+                        result
+                            .iter()
+                            .map(|(combobox, _)| describe(combobox))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    );
+
+                    carried.extend(result.into_iter().map(|(combobox, _)| combobox));
+                    carried.sort_by_key(box_key);
+
+                    merges.push((
+                        State {
+                            carried,
+                            merged: true,
+                            ..self.clone()
+                        },
+                        description,
+                    ));
+                }
+            }
+        }
+
+        merges
+    }
+}
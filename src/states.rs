@@ -6,6 +6,7 @@ pub enum GuiState {
     Level,
     LevelCompleted,
     Credits,
+    Editor,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Hash, Copy)]
@@ -18,6 +19,7 @@ pub enum AudioState {
 pub enum LevelState {
     None,
     Level,
+    Editor,
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Hash, Copy)]
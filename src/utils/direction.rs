@@ -1,7 +1,7 @@
 use bevy::prelude::*;
-use bevy_rapier2d::plugin::RapierConfiguration;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Copy)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SceneDirection {
     Down,
     Right,
@@ -46,8 +46,8 @@ impl SceneDirection {
         }
     }
 
-    pub fn from_gravity_direction(config: &RapierConfiguration) -> Self {
-        let dir = config.gravity.normalize();
+    pub fn from_gravity_direction(gravity: Vec2) -> Self {
+        let dir = gravity.normalize_or_zero();
         if dir.y > 0.1 {
             return SceneDirection::Up;
         }
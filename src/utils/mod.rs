@@ -1,5 +1,7 @@
 mod direction;
 mod fps;
+mod spatial_hash;
 
 pub use direction::*;
 pub use fps::*;
+pub use spatial_hash::*;
@@ -0,0 +1,50 @@
+use std::hash::Hash;
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+/// Buckets rectangles into a uniform grid to find the ones that may overlap without
+/// comparing every pair.
+pub struct SpatialHash<T> {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<T>>,
+}
+
+impl<T: Copy + Ord + Hash> SpatialHash<T> {
+    pub fn new(cell_size: f32) -> SpatialHash<T> {
+        SpatialHash {
+            cell_size,
+            cells: HashMap::default(),
+        }
+    }
+
+    pub fn insert(&mut self, min: Vec2, max: Vec2, item: T) {
+        let min = (min / self.cell_size).floor().as_ivec2();
+        let max = (max / self.cell_size).floor().as_ivec2();
+
+        for x in min.x..=max.x {
+            for y in min.y..=max.y {
+                self.cells.entry(IVec2::new(x, y)).or_default().push(item);
+            }
+        }
+    }
+
+    /// Every pair of items sharing a cell, once, as `(smaller, larger)` in ascending order.
+    /// The rectangles of a pair only may overlap, the caller still has to check.
+    pub fn pairs(&self) -> Vec<(T, T)> {
+        let mut pairs = vec![];
+
+        for items in self.cells.values() {
+            for (i, a) in items.iter().enumerate() {
+                for b in &items[i + 1..] {
+                    pairs.push(((*a).min(*b), (*a).max(*b)));
+                }
+            }
+        }
+
+        // Cells are visited in no particular order
+        pairs.sort();
+        pairs.dedup();
+        pairs
+    }
+}
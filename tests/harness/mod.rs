@@ -0,0 +1,195 @@
+//! Runs the game without a window, renderer or audio output, one simulation step a frame.
+
+#![allow(dead_code)]
+
+use bevy::asset::AssetPlugin;
+use bevy::hierarchy::HierarchyPlugin;
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::{ButtonState, InputPlugin};
+use bevy::prelude::*;
+use bevy::time::{FixedTimesteps, TimePlugin};
+use bevy::transform::TransformPlugin;
+use bevy::utils::Duration;
+use bevy_kira_audio::{Audio, AudioInstance, AudioSource};
+use post_processing::AmbientLight;
+
+use combobox_game::core::{CorePlugin, FinishPoint, Material, Player, FIXED_DT};
+use combobox_game::levels::{
+    CurrentLevel, LevelDescription, LevelInfo, LevelPlugin, LevelRegistry, LevelRoot, PendingLevel,
+    SaveDir,
+};
+use combobox_game::states::{AudioState, CameraState, GuiState, LevelState};
+
+/// Frames to wait for assets before giving up.
+const MAX_LOADING_FRAMES: usize = 600;
+
+/// Distance at which `finish_level` counts a player as finished.
+const FINISH_DISTANCE: f32 = 120.0;
+
+pub struct Harness {
+    pub app: App,
+}
+
+impl Harness {
+    pub fn new() -> Harness {
+        let mut app = App::new();
+
+        // Frames take exactly one step, however long they really take
+        app.add_plugins_with(MinimalPlugins, |group| group.disable::<TimePlugin>());
+        app.init_resource::<Time>();
+        app.init_resource::<FixedTimesteps>();
+        app.add_system_to_stage(CoreStage::First, advance_time);
+        app.add_plugin(TransformPlugin);
+        app.add_plugin(HierarchyPlugin);
+        app.add_plugin(InputPlugin);
+        app.add_plugin(AssetPlugin);
+
+        // Normally registered by the render and audio plugins
+        app.add_asset::<Mesh>();
+        app.add_asset::<Image>();
+        app.add_asset::<Material>();
+        app.add_asset::<AudioSource>();
+        app.add_asset::<AudioInstance>();
+        app.insert_resource(Audio::default());
+        app.insert_resource(AmbientLight {
+            color: Color::WHITE,
+        });
+        app.init_resource::<ClearColor>();
+
+        app.insert_resource(SaveDir(None));
+        app.add_state(GuiState::Level);
+        app.add_state(AudioState::Level);
+        app.add_state(LevelState::None);
+        app.add_state(CameraState::None);
+
+        app.add_plugin(LevelPlugin);
+        app.add_plugin(CorePlugin);
+
+        let mut harness = Harness { app };
+        harness.step_until("the level registry to load", |app| {
+            !app.world.resource::<LevelRegistry>().is_empty()
+        });
+        harness
+    }
+
+    /// Starts a level from `assets/levels/levels.registry.ron`.
+    pub fn load_level(&mut self, id: &str) {
+        assert!(
+            self.app.world.resource::<LevelRegistry>().get(id).is_some(),
+            "there is no level {}",
+            id
+        );
+
+        self.app.world.resource_mut::<CurrentLevel>().id = id.to_string();
+        self.app
+            .world
+            .resource_mut::<State<LevelState>>()
+            .set(LevelState::Level)
+            .unwrap();
+
+        self.step_until("the level to be built", |app| {
+            let mut roots = app
+                .world
+                .query_filtered::<(), (With<LevelRoot>, Without<PendingLevel>)>();
+            let mut players = app.world.query_filtered::<(), With<Player>>();
+            roots.iter(&app.world).next().is_some() && players.iter(&app.world).next().is_some()
+        });
+    }
+
+    /// Starts a level that only exists in the test.
+    pub fn load_description(&mut self, description: LevelDescription) {
+        let handle = self
+            .app
+            .world
+            .resource_mut::<Assets<LevelDescription>>()
+            .add(description);
+
+        self.app.world.resource_mut::<LevelRegistry>().insert(
+            LevelInfo {
+                id: "test".to_string(),
+                name: "Test".to_string(),
+                thumbnail: String::new(),
+                path: String::new(),
+                order: 0,
+            },
+            handle,
+        );
+
+        self.load_level("test");
+    }
+
+    pub fn step(&mut self, frames: usize) {
+        for _ in 0..frames {
+            self.app.update();
+        }
+    }
+
+    fn step_until(&mut self, what: &str, done: impl Fn(&mut App) -> bool) {
+        for _ in 0..MAX_LOADING_FRAMES {
+            if done(&mut self.app) {
+                return;
+            }
+            self.app.update();
+        }
+        panic!("gave up waiting for {}", what);
+    }
+
+    pub fn press(&mut self, key: KeyCode) {
+        self.send_key(key, ButtonState::Pressed);
+    }
+
+    pub fn release(&mut self, key: KeyCode) {
+        self.send_key(key, ButtonState::Released);
+    }
+
+    /// Holds `key` down for `frames` frames.
+    pub fn hold(&mut self, key: KeyCode, frames: usize) {
+        self.press(key);
+        self.step(frames);
+        self.release(key);
+    }
+
+    fn send_key(&mut self, key: KeyCode, state: ButtonState) {
+        self.app.world.send_event(KeyboardInput {
+            scan_code: 0,
+            key_code: Some(key),
+            state,
+        });
+    }
+
+    /// Clones of every `T` in the level.
+    pub fn components<T: Component + Clone>(&mut self) -> Vec<T> {
+        let mut query = self.app.world.query::<&T>();
+        query.iter(&self.app.world).cloned().collect()
+    }
+
+    /// World positions of everything with a `T`, in pixels.
+    pub fn positions<T: Component>(&mut self) -> Vec<Vec2> {
+        let mut query = self.app.world.query_filtered::<&GlobalTransform, With<T>>();
+        query
+            .iter(&self.app.world)
+            .map(|transform| transform.translation().truncate())
+            .collect()
+    }
+
+    pub fn players_finished(&mut self) -> bool {
+        let finish_points = self.positions::<FinishPoint>();
+        let players = self.positions::<Player>();
+
+        !players.is_empty()
+            && players.iter().all(|player| {
+                finish_points
+                    .iter()
+                    .any(|finish| player.distance(*finish) < FINISH_DISTANCE)
+            })
+    }
+
+    pub fn gui_state(&self) -> GuiState {
+        *self.app.world.resource::<State<GuiState>>().current()
+    }
+}
+
+fn advance_time(mut time: ResMut<Time>) {
+    let last = time.last_update().unwrap_or_else(|| time.startup());
+    time.update_with_instant(last + Duration::from_secs_f32(FIXED_DT));
+}
@@ -0,0 +1,747 @@
+use bevy::prelude::*;
+
+use combobox_game::core::{
+    BodyGravity, BoxKind, ButtonMode, CameraSettings, Combobox, ComboboxType, Door, Elevator,
+    ElevatorType, FinishPoint, Gate, GateKind, GravityBoots, GravityRule, MergeRecipe,
+    MergeRecipes, OffsetsRule, PiecesRule, Player, PlayerIndex, PositionRule, TypeRule, WeightRule,
+};
+use combobox_game::levels::{
+    read_levels, LevelDescription, LevelObject, Replay, Replays, REWIND_KEY, WATCH_REPLAY_KEY,
+};
+use combobox_game::states::GuiState;
+use combobox_game::utils::SceneDirection;
+
+mod harness;
+
+use harness::Harness;
+
+/// A flat floor at `y = 0` with the player standing at the origin, `objects` are added first.
+fn flat_level(mut objects: Vec<LevelObject>) -> LevelDescription {
+    objects.extend([
+        LevelObject::Wall {
+            left: -10.0,
+            right: 30.0,
+            bottom: -5.0,
+            top: 0.0,
+        },
+        LevelObject::SpawnPoint {
+            x: 0.0,
+            y: 2.0,
+            index: PlayerIndex::SinglePlayer,
+        },
+    ]);
+
+    LevelDescription {
+        objects,
+        ..default()
+    }
+}
+
+fn finish_point(x: f32) -> LevelObject {
+    LevelObject::FinishPoint {
+        x,
+        y: 1.5,
+        signal: None,
+    }
+}
+
+/// A door at `x = 10.5`, closing the way to the finish until `signal` turns on.
+fn door(signal: &str) -> LevelObject {
+    LevelObject::Door {
+        x: 10.5,
+        y: 1.25,
+        height: 2.5,
+        direction: SceneDirection::Up,
+        signal: signal.to_string(),
+    }
+}
+
+/// Plays a [`flat_level`] with `objects`, runs `input` and gives doors and elevators two
+/// seconds to move. Returns the first `T` of the level.
+fn settle<T: Component + Clone>(objects: Vec<LevelObject>, input: impl FnOnce(&mut Harness)) -> T {
+    let mut harness = Harness::new();
+    harness.load_description(flat_level(objects));
+    input(&mut harness);
+    harness.step(120);
+    harness.components::<T>().remove(0)
+}
+
+fn standard_box(x: f32) -> LevelObject {
+    LevelObject::Box {
+        x,
+        y: 0.5,
+        combobox: Combobox::new(1.0, ComboboxType::Standard { group: 1 }),
+    }
+}
+
+#[test]
+fn every_level_builds() {
+    let levels = read_levels(std::path::Path::new("assets")).unwrap();
+    assert!(!levels.is_empty());
+
+    for (info, _) in levels {
+        let mut harness = Harness::new();
+        harness.load_level(&info.id);
+        harness.step(60);
+
+        assert_eq!(harness.positions::<Player>().len(), 1, "level {}", info.id);
+        assert!(
+            !harness.positions::<FinishPoint>().is_empty(),
+            "level {}",
+            info.id
+        );
+    }
+}
+
+#[test]
+fn walking_right_reaches_finish() {
+    let mut harness = Harness::new();
+    harness.load_description(flat_level(vec![finish_point(8.0)]));
+
+    harness.press(KeyCode::D);
+    for _ in 0..600 {
+        if harness.players_finished() {
+            break;
+        }
+        harness.step(1);
+    }
+    harness.release(KeyCode::D);
+
+    assert!(harness.players_finished());
+
+    // Players have to stay at the finish for a second
+    harness.step(90);
+    assert_eq!(harness.gui_state(), GuiState::LevelCompleted);
+}
+
+#[test]
+fn touching_boxes_merge() {
+    let mut harness = Harness::new();
+    harness.load_description(flat_level(vec![
+        standard_box(4.5),
+        standard_box(5.5),
+        finish_point(20.0),
+    ]));
+    harness.step(60);
+
+    let weights: Vec<f32> = harness
+        .components::<Combobox>()
+        .iter()
+        .map(|combobox| combobox.weight)
+        .collect();
+    assert_eq!(weights, vec![2.0]);
+}
+
+#[test]
+fn separate_pairs_merge_in_the_same_frame() {
+    let mut harness = Harness::new();
+    harness.load_description(flat_level(vec![
+        standard_box(4.5),
+        standard_box(5.5),
+        standard_box(12.5),
+        standard_box(13.5),
+        finish_point(20.0),
+    ]));
+
+    let merged = |harness: &mut Harness| {
+        harness
+            .components::<Combobox>()
+            .iter()
+            .filter(|combobox| combobox.weight == 2.0)
+            .count()
+    };
+
+    for _ in 0..60 {
+        if merged(&mut harness) > 0 {
+            break;
+        }
+        harness.step(1);
+    }
+
+    assert_eq!(merged(&mut harness), 2);
+}
+
+#[test]
+fn split_box_cuts_box_into_pieces() {
+    let mut harness = Harness::new();
+    harness.load_description(flat_level(vec![
+        LevelObject::Box {
+            x: 6.5,
+            y: 0.5,
+            combobox: Combobox::new(1.0, ComboboxType::Split(2)),
+        },
+        LevelObject::Box {
+            x: 8.0,
+            y: 1.0,
+            combobox: Combobox::new(4.0, ComboboxType::Standard { group: 1 }),
+        },
+        finish_point(20.0),
+    ]));
+    harness.step(60);
+
+    let boxes = harness.components::<Combobox>();
+    assert_eq!(boxes.len(), 2);
+    assert!(boxes.iter().all(|combobox| combobox.weight == 2.0));
+    assert!(boxes.iter().all(|combobox| combobox.split_pieces() == 2));
+}
+
+#[test]
+fn undoing_a_buff_keeps_its_offsets() {
+    let buff = Combobox::new(1.0, ComboboxType::Buff(2.0));
+    let standard = Combobox::new(1.0, ComboboxType::Standard { group: 1 });
+    let recipes = MergeRecipes::builtin();
+    let (recipe, swapped) = recipes.find(&buff, &standard).unwrap();
+    assert!(!swapped);
+
+    let merged = recipe.apply(&buff, Vec2::new(1.0, 2.0), &standard, Vec2::new(3.0, 1.0));
+
+    // The buff comes apart at the buffed box, the box where it was seen from the buff
+    assert_eq!(merged.len(), 1);
+    let (buffed, position) = &merged[0];
+    assert_eq!(*position, Vec2::new(3.0, 1.0));
+    assert_eq!(
+        buffed.combined_from,
+        vec![(buff, Vec2::ZERO), (standard, Vec2::new(2.0, -1.0))]
+    );
+}
+
+#[test]
+fn undo_box_joins_split_pieces_back() {
+    let mut harness = Harness::new();
+    harness.load_description(flat_level(vec![
+        LevelObject::Box {
+            x: 6.5,
+            y: 0.5,
+            combobox: Combobox::new(1.0, ComboboxType::Split(2)),
+        },
+        LevelObject::Box {
+            x: 8.0,
+            y: 1.0,
+            combobox: Combobox::new(4.0, ComboboxType::Standard { group: 1 }),
+        },
+        // Lands on one of the pieces
+        LevelObject::Box {
+            x: 9.0,
+            y: 5.0,
+            combobox: Combobox::new(1.0, ComboboxType::Undo),
+        },
+        finish_point(20.0),
+    ]));
+
+    // The joined box and the split box may cut it again later, look right after the undo
+    let is_undo = |combobox: &Combobox| combobox.box_type == ComboboxType::Undo;
+    let mut boxes = harness.components::<Combobox>();
+    for _ in 0..120 {
+        if !boxes.iter().any(is_undo) {
+            break;
+        }
+        harness.step(1);
+        boxes = harness.components::<Combobox>();
+    }
+
+    assert!(
+        !boxes.iter().any(is_undo),
+        "the undo box never touched a piece"
+    );
+    assert_eq!(boxes.len(), 2);
+    assert!(boxes.iter().all(|combobox| combobox.split_pieces() == 1));
+    assert!(boxes.contains(&Combobox::new(4.0, ComboboxType::Standard { group: 1 })));
+    assert!(boxes.contains(&Combobox::new(1.0, ComboboxType::Split(2))));
+}
+
+#[test]
+fn level_recipes_mix_lamps() {
+    let lamp = |x, color| LevelObject::Box {
+        x,
+        y: 0.5,
+        combobox: Combobox::new(1.0, ComboboxType::Lamp { color }),
+    };
+
+    let mut harness = Harness::new();
+    harness.load_description(LevelDescription {
+        recipes: vec![MergeRecipe {
+            first: BoxKind::Lamp,
+            second: BoxKind::Lamp,
+            same_variant: false,
+            weight: WeightRule::Sum,
+            box_type: TypeRule::MixColors,
+            gravity: GravityRule::Common,
+            position: PositionRule::Center,
+            pieces: PiecesRule::One,
+            undoable: true,
+            undo_offsets: OffsetsRule::Piece,
+        }],
+        ..flat_level(vec![
+            lamp(4.5, Color::RED),
+            lamp(5.5, Color::BLUE),
+            finish_point(20.0),
+        ])
+    });
+    harness.step(60);
+
+    let boxes = harness.components::<Combobox>();
+    assert_eq!(boxes.len(), 1);
+    assert_eq!(
+        boxes[0].box_type,
+        ComboboxType::Lamp {
+            color: Color::rgb(0.5, 0.0, 0.5)
+        }
+    );
+}
+
+#[test]
+fn box_on_button_opens_door() {
+    let mut harness = Harness::new();
+    harness.load_description(flat_level(vec![
+        standard_box(4.5),
+        LevelObject::Button {
+            x: 4.5,
+            y: 0.5,
+            direction: SceneDirection::Up,
+            signal: "door".to_string(),
+            mode: ButtonMode::Momentary,
+        },
+        door("door"),
+        finish_point(20.0),
+    ]));
+    harness.step(120);
+
+    let doors = harness.components::<Door>();
+    assert_eq!(doors.len(), 1);
+    assert_eq!(doors[0].progress, 1.0);
+}
+
+#[test]
+fn pressure_plate_needs_enough_weight() {
+    let door_progress = |weight: f32| {
+        let objects = vec![
+            LevelObject::Box {
+                x: 4.5,
+                y: weight.sqrt() * 0.5,
+                combobox: Combobox::new(weight, ComboboxType::Standard { group: 1 }),
+            },
+            LevelObject::PressurePlate {
+                x: 4.5,
+                y: 0.5,
+                direction: SceneDirection::Up,
+                signal: "door".to_string(),
+                min_weight: 2.0,
+                max_weight: Some(3.0),
+            },
+            door("door"),
+            finish_point(20.0),
+        ];
+        settle::<Door>(objects, |_| {}).progress
+    };
+
+    assert_eq!(door_progress(1.0), 0.0);
+    assert_eq!(door_progress(2.0), 1.0);
+    assert_eq!(door_progress(4.0), 0.0);
+}
+
+#[test]
+fn gates_combine_signals() {
+    let button = |x, signal: &str| LevelObject::Button {
+        x,
+        y: 0.5,
+        direction: SceneDirection::Up,
+        signal: signal.to_string(),
+        mode: ButtonMode::Momentary,
+    };
+    let gate = |output: &str, kind| Gate {
+        output: output.to_string(),
+        kind,
+    };
+
+    let mut harness = Harness::new();
+    harness.load_description(LevelDescription {
+        logic: vec![
+            gate("open", GateKind::And(vec!["a".into(), "not b".into()])),
+            gate("not b", GateKind::Not("b".into())),
+        ],
+        ..flat_level(vec![
+            standard_box(4.5),
+            button(4.5, "a"),
+            button(6.5, "b"),
+            door("open"),
+            finish_point(20.0),
+        ])
+    });
+    harness.step(120);
+
+    assert_eq!(harness.components::<Door>()[0].progress, 1.0);
+}
+
+#[test]
+fn latching_button_stays_on_after_player_leaves() {
+    let door_progress = |mode: ButtonMode| {
+        let objects = vec![
+            LevelObject::Button {
+                x: 3.5,
+                y: 0.5,
+                direction: SceneDirection::Up,
+                signal: "door".to_string(),
+                mode,
+            },
+            // Behind the player, always on
+            standard_box(-3.5),
+            LevelObject::Button {
+                x: -3.5,
+                y: 0.5,
+                direction: SceneDirection::Up,
+                signal: "reset".to_string(),
+                mode: ButtonMode::Momentary,
+            },
+            door("door"),
+            finish_point(20.0),
+        ];
+        settle::<Door>(objects, |harness| harness.hold(KeyCode::D, 100)).progress
+    };
+    let latch = |reset: &str| ButtonMode::Latch {
+        reset: Some(reset.to_string()),
+    };
+
+    assert_eq!(door_progress(ButtonMode::Momentary), 0.0);
+    assert_eq!(door_progress(ButtonMode::Toggle), 1.0);
+    assert_eq!(door_progress(ButtonMode::Latch { reset: None }), 1.0);
+    assert_eq!(door_progress(latch("unused")), 1.0);
+    assert_eq!(door_progress(latch("reset")), 0.0);
+    // Still counting down
+    assert_eq!(door_progress(ButtonMode::Timed { seconds: 5.0 }), 1.0);
+    assert_eq!(door_progress(ButtonMode::Timed { seconds: 0.5 }), 0.0);
+}
+
+#[test]
+fn rewinding_unlatches_button() {
+    let objects = vec![
+        LevelObject::Button {
+            x: 3.5,
+            y: 0.5,
+            direction: SceneDirection::Up,
+            signal: "door".to_string(),
+            mode: ButtonMode::Latch { reset: None },
+        },
+        door("door"),
+        finish_point(20.0),
+    ];
+    let door = settle::<Door>(objects, |harness| {
+        harness.hold(KeyCode::D, 100);
+        harness.press(REWIND_KEY);
+        harness.step(10);
+        let rewound = harness.components::<Door>()[0].progress;
+        assert!(rewound > 0.0);
+
+        // Stays where rewinding left it for the step the key is released in
+        harness.release(REWIND_KEY);
+        harness.step(1);
+        assert_eq!(harness.components::<Door>()[0].progress, rewound);
+
+        harness.step(60);
+        // Back to before the player reached the button
+        harness.hold(REWIND_KEY, 150);
+    });
+
+    assert_eq!(door.progress, 0.0);
+}
+
+#[test]
+fn triggered_elevator_follows_its_signal() {
+    let progress = |boxes: Vec<LevelObject>, returns: bool, input: fn(&mut Harness)| {
+        let objects = boxes
+            .into_iter()
+            .chain([
+                LevelObject::Button {
+                    x: 4.5,
+                    y: 0.5,
+                    direction: SceneDirection::Up,
+                    signal: "lift".to_string(),
+                    mode: ButtonMode::Momentary,
+                },
+                LevelObject::Elevator {
+                    start: Vec2::new(12.0, 0.2),
+                    end: Vec2::new(12.0, 3.0),
+                    elevator_type: ElevatorType::Triggered {
+                        seconds: 1.0,
+                        returns,
+                        progress: 0.0,
+                    },
+                    signal: Some("lift".to_string()),
+                },
+                finish_point(20.0),
+            ])
+            .collect();
+        match settle::<Elevator>(objects, input).elevator_type {
+            ElevatorType::Triggered { progress, .. } => progress,
+            _ => unreachable!(),
+        }
+    };
+
+    assert_eq!(progress(vec![], true, |_| {}), 0.0);
+    assert_eq!(progress(vec![standard_box(4.5)], true, |_| {}), 1.0);
+
+    // The player only presses the button on the way past
+    let walk_past: fn(&mut Harness) = |harness| harness.hold(KeyCode::D, 100);
+    assert_eq!(progress(vec![], true, walk_past), 0.0);
+    let stopped = progress(vec![], false, walk_past);
+    assert!(stopped > 0.0 && stopped < 1.0);
+}
+
+#[test]
+fn elevator_carries_rider_and_stops_above_boxes() {
+    let elevator = |start: f32, end: f32| LevelObject::Elevator {
+        start: Vec2::new(0.0, start),
+        end: Vec2::new(0.0, end),
+        elevator_type: ElevatorType::OneShot {
+            seconds: 1.0,
+            progress: 0.0,
+        },
+        signal: None,
+    };
+
+    let mut harness = Harness::new();
+    harness.load_description(flat_level(vec![elevator(0.2, 4.0)]));
+    harness.step(120);
+    assert!(harness.positions::<Player>()[0].y > 4.0 * 50.0);
+
+    let mut harness = Harness::new();
+    harness.load_description(flat_level(vec![standard_box(0.0), elevator(4.0, 0.2)]));
+    harness.step(120);
+    assert!(harness.positions::<Elevator>()[0].y > 2.0 * 50.0);
+    assert!(harness.positions::<Combobox>()[0].y > 0.0);
+}
+
+#[test]
+fn gravity_zone_lifts_what_is_inside() {
+    let mut harness = Harness::new();
+    harness.load_description(flat_level(vec![
+        standard_box(5.0),
+        standard_box(10.0),
+        LevelObject::GravityZone {
+            left: -2.0,
+            right: 2.0,
+            bottom: 0.0,
+            top: 3.0,
+            direction: SceneDirection::Right,
+            strength: 0.0,
+        },
+        LevelObject::GravityZone {
+            left: 4.0,
+            right: 6.0,
+            bottom: 0.0,
+            top: 3.0,
+            direction: SceneDirection::Up,
+            strength: 1.0,
+        },
+    ]));
+    harness.step(60);
+
+    let boxes = harness.positions::<Combobox>();
+    assert!(boxes.iter().any(|position| position.y > 1.5 * 50.0));
+    assert!(boxes.iter().any(|position| position.y < 1.0 * 50.0));
+    // Only the player stands in the zone without gravity
+    let weightless = harness
+        .components::<BodyGravity>()
+        .iter()
+        .filter(|gravity| gravity.0 == Vec2::ZERO)
+        .count();
+    assert_eq!(weightless, 1);
+}
+
+#[test]
+fn boots_box_gives_player_its_gravity() {
+    let boots = |local_gravity| LevelObject::Box {
+        x: 2.0,
+        y: 0.5,
+        combobox: Combobox {
+            local_gravity,
+            ..Combobox::new(1.0, ComboboxType::Boots)
+        },
+    };
+
+    let mut harness = Harness::new();
+    harness.load_description(flat_level(vec![boots(Some(Vec2::NEG_X))]));
+    harness.step(60);
+    assert_eq!(
+        harness.components::<GravityBoots>(),
+        vec![GravityBoots {
+            direction: Vec2::NEG_X
+        }]
+    );
+    assert!(harness.positions::<Combobox>().is_empty());
+}
+
+#[test]
+fn keys_follow_the_rotated_camera() {
+    // Gravity pulls the player onto a wall, which is the floor on a rotated screen
+    let climbed = |rotate_camera, key| {
+        let mut harness = Harness::new();
+        harness.load_description(LevelDescription {
+            rotate_camera: Some(rotate_camera),
+            ..flat_level(vec![
+                LevelObject::Wall {
+                    left: -3.0,
+                    right: -1.0,
+                    bottom: 0.0,
+                    top: 10.0,
+                },
+                LevelObject::GravityZone {
+                    left: -1.0,
+                    right: 5.0,
+                    bottom: 0.0,
+                    top: 10.0,
+                    direction: SceneDirection::Left,
+                    strength: 1.0,
+                },
+            ])
+        });
+        harness.step(60);
+        let start = harness.positions::<Player>()[0];
+        harness.hold(key, 60);
+        harness.positions::<Player>()[0].y - start.y
+    };
+
+    // Up the wall is left on the screen once it turns
+    assert!(climbed(true, KeyCode::A) > 50.0);
+    assert!(climbed(true, KeyCode::W) < 50.0);
+    assert!(climbed(false, KeyCode::W) > 50.0);
+    assert!(climbed(false, KeyCode::A) < 50.0);
+}
+
+#[test]
+fn replay_turns_the_keys_like_the_recorded_camera() {
+    let description = || {
+        flat_level(vec![
+            LevelObject::GravityZone {
+                left: 2.0,
+                right: 12.0,
+                bottom: 0.0,
+                top: 10.0,
+                direction: SceneDirection::Right,
+                strength: 1.0,
+            },
+            LevelObject::Wall {
+                left: 12.0,
+                right: 14.0,
+                bottom: 0.0,
+                top: 10.0,
+            },
+        ])
+    };
+    let record = |rotate| {
+        let mut harness = Harness::new();
+        harness.load_description(description());
+        harness
+            .app
+            .world
+            .resource_mut::<CameraSettings>()
+            .rotate_with_gravity = rotate;
+        // Walks into the zone, where gravity turns and the keys with it
+        harness.hold(KeyCode::D, 60);
+        harness.step(60);
+        harness.hold(KeyCode::D, 60);
+        harness.hold(KeyCode::W, 40);
+        (
+            harness.positions::<Player>(),
+            harness.app.world.resource::<Replays>().recording.clone(),
+        )
+    };
+    let (rotated, replay) = record(true);
+    let (unrotated, _) = record(false);
+    assert_ne!(rotated, unrotated);
+
+    // Played with the camera setting the other way
+    let mut harness = Harness::new();
+    harness
+        .app
+        .world
+        .resource_mut::<Replays>()
+        .play(replay.clone());
+    harness.load_description(description());
+    // The first step runs once the level is loaded
+    harness.step(replay.len() - 1);
+
+    assert_eq!(harness.positions::<Player>(), rotated);
+}
+
+#[test]
+fn same_input_plays_out_the_same() {
+    let run = || {
+        let mut harness = Harness::new();
+        harness.load_description(flat_level(vec![standard_box(3.5), finish_point(20.0)]));
+        harness.hold(KeyCode::D, 45);
+        harness.hold(KeyCode::Space, 10);
+        harness.hold(KeyCode::A, 30);
+        harness.step(30);
+        (
+            harness.positions::<Player>(),
+            harness.positions::<Combobox>(),
+        )
+    };
+
+    assert_eq!(run(), run());
+}
+
+#[test]
+fn saved_replay_plays_out_the_same() {
+    let description = || flat_level(vec![standard_box(3.5), finish_point(20.0)]);
+    let frames = 115;
+
+    let mut harness = Harness::new();
+    harness.load_description(description());
+    harness.hold(KeyCode::D, 45);
+    harness.hold(KeyCode::Space, 10);
+    harness.hold(KeyCode::A, 30);
+    harness.step(frames - 85);
+    let recorded = (
+        harness.positions::<Player>(),
+        harness.positions::<Combobox>(),
+    );
+
+    let path = std::env::temp_dir().join(format!("combobox-replay-{}.ron", std::process::id()));
+    let recording = harness.app.world.resource::<Replays>().recording.clone();
+    recording.save(&path).unwrap();
+    let replay = Replay::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(replay.len(), recording.len());
+
+    let mut harness = Harness::new();
+    harness.app.world.resource_mut::<Replays>().play(replay);
+    harness.load_description(description());
+    harness.step(frames);
+    let replayed = (
+        harness.positions::<Player>(),
+        harness.positions::<Combobox>(),
+    );
+
+    assert_eq!(recorded, replayed);
+
+    // Stops once there are no keys left
+    harness.step(2);
+    assert!(!harness.app.world.resource::<Replays>().is_playing());
+}
+
+#[test]
+fn stopped_replay_releases_its_keys() {
+    for watch_key in [false, true] {
+        let mut replay = Replay::new("test");
+        for _ in 0..60 {
+            replay.push(vec![KeyCode::D], false);
+        }
+
+        let mut harness = Harness::new();
+        harness.app.world.resource_mut::<Replays>().play(replay);
+        harness.load_description(flat_level(vec![finish_point(20.0)]));
+        if watch_key {
+            harness.step(30);
+            harness.hold(WATCH_REPLAY_KEY, 1);
+            harness.step(1);
+        } else {
+            // Runs out of keys while D is held
+            harness.step(62);
+        }
+
+        assert!(!harness.app.world.resource::<Replays>().is_playing());
+        let keys = harness.app.world.resource::<Input<KeyCode>>();
+        assert_eq!(keys.get_pressed().count(), 0, "watch key {}", watch_key);
+    }
+}